@@ -5,7 +5,7 @@ use raytracer::{
 };
 use std::env;
 
-use raytracer::{HittableList, Sphere, Vec3};
+use raytracer::{GradientSky, HittableList, PathIntegrator, Plane, Scene, Sphere, Vec3};
 
 fn main() -> Result<()> {
     env::set_var("RUST_LOG", "debug");
@@ -14,9 +14,9 @@ fn main() -> Result<()> {
     let mut world = HittableList::default();
 
     let ground_material = Lambertian::new(Vec3::new(0.5, 0.5, 0.5));
-    world.objects.push(Box::new(Sphere::new(
-        Vec3::new(0.0, -1000.0, 0.0),
-        1000.0,
+    world.objects.push(Box::new(Plane::new(
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
         ground_material,
     )));
 
@@ -96,7 +96,6 @@ fn main() -> Result<()> {
     cam.aspect_radio = 16.0 / 9.0;
     cam.image_width = 1200.0;
     cam.samples_per_pixel = 500;
-    cam.max_depth = 50;
 
     cam.vfov = 20.0;
     cam.look_from = Vec3::new(13.0, 2.0, 3.0);
@@ -105,6 +104,7 @@ fn main() -> Result<()> {
     cam.defocus_angle = 0.6;
     cam.focus_dist = 10.0;
 
-    cam.render(&world)?;
+    let scene = Scene::new(world, Box::new(GradientSky::default()));
+    cam.render(&scene, &PathIntegrator::new(50))?;
     Ok(())
 }
@@ -0,0 +1,118 @@
+use crate::{Interval, Ray, Vec3};
+
+/// Axis-aligned bounding box, stored as one interval per axis.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+impl Aabb {
+    pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        let mut bbox = Self { x, y, z };
+        bbox.pad_to_minimums();
+        bbox
+    }
+
+    /// Treat the two points a and b as extrema for the bounding box, so we don't require a
+    /// particular minimum/maximum coordinate order.
+    pub fn from_points(a: Vec3, b: Vec3) -> Self {
+        Self::new(
+            Interval::new(a.x.min(b.x), a.x.max(b.x)),
+            Interval::new(a.y.min(b.y), a.y.max(b.y)),
+            Interval::new(a.z.min(b.z), a.z.max(b.z)),
+        )
+    }
+
+    /// Tight bounds of a circle of `radius` around `center` in the plane facing `normal`.
+    pub fn from_disk(center: Vec3, normal: Vec3, radius: f64) -> Self {
+        // Extent along each axis is radius * sin(angle between axis and normal).
+        let extent = |n: f64| radius * (1.0 - n * n).max(0.0).sqrt();
+        let e = Vec3::new(extent(normal.x), extent(normal.y), extent(normal.z));
+        Self::from_points(center - e, center + e)
+    }
+
+    pub fn surrounding(a: &Aabb, b: &Aabb) -> Self {
+        Self {
+            x: Interval::enclosing(&a.x, &b.x),
+            y: Interval::enclosing(&a.y, &b.y),
+            z: Interval::enclosing(&a.z, &b.z),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            x: Interval::empty(),
+            y: Interval::empty(),
+            z: Interval::empty(),
+        }
+    }
+
+    pub fn universe() -> Self {
+        Self {
+            x: Interval::universe(),
+            y: Interval::universe(),
+            z: Interval::universe(),
+        }
+    }
+
+    pub fn axis_interval(&self, n: usize) -> &Interval {
+        match n {
+            1 => &self.y,
+            2 => &self.z,
+            _ => &self.x,
+        }
+    }
+
+    /// Returns the part of `ray_t` during which the ray is inside the box, if any.
+    pub fn hit(&self, ray: &Ray, mut ray_t: Interval) -> Option<Interval> {
+        for axis in 0..3 {
+            let ax = self.axis_interval(axis);
+            let adinv = 1.0 / ray.direction[axis];
+
+            let t0 = (ax.min - ray.origin[axis]) * adinv;
+            let t1 = (ax.max - ray.origin[axis]) * adinv;
+            let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+
+            ray_t.min = ray_t.min.max(t0);
+            ray_t.max = ray_t.max.min(t1);
+            if ray_t.max <= ray_t.min {
+                return None;
+            }
+        }
+        Some(ray_t)
+    }
+
+    /// Adjust the AABB so that no side is narrower than some delta, padding if necessary.
+    fn pad_to_minimums(&mut self) {
+        let delta = 0.0001;
+        for axis in [&mut self.x, &mut self.y, &mut self.z] {
+            if axis.size() < delta {
+                *axis = axis.expand(delta);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hit() {
+        let bbox = Aabb::from_points(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let t = bbox.hit(&ray, Interval::new(0.0, f64::INFINITY)).unwrap();
+        assert_eq!((t.min, t.max), (4.0, 6.0));
+
+        let miss = Ray::new(Vec3::new(0.0, 2.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(bbox.hit(&miss, Interval::new(0.0, f64::INFINITY)).is_none());
+    }
+
+    #[test]
+    fn test_pad_flat_box() {
+        let bbox = Aabb::from_points(Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0));
+        assert!(bbox.z.size() > 0.0);
+    }
+}
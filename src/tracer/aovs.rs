@@ -0,0 +1,198 @@
+use std::path::Path;
+
+use anyhow::Result;
+use exr::prelude::*;
+
+use crate::{Color, HitRecord, Ray, Vec3};
+
+/// Auxiliary output values (AOVs) for one camera sample: guides for denoisers and the
+/// beauty image split into lighting passes for compositing.
+///
+/// The lighting passes are split by the first scattering event, so they add up to the
+/// beauty image: `emission` is light seen directly, `direct_diffuse` and
+/// `indirect_diffuse` are light after one or more bounces off a non-specular first
+/// surface, and `specular` is everything seen through a perfect mirror or refraction.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Aovs {
+    pub albedo: Color, // Albedo of the first surface hit
+    pub normal: Vec3,  // Shading normal at the first hit, in world space
+    pub depth: f64,    // Distance to the first hit; zero for misses
+    pub alpha: f64,    // One where the camera ray hit the world, zero where it escaped
+    pub direct_diffuse: Color,
+    pub indirect_diffuse: Color,
+    pub specular: Color,
+    pub emission: Color,
+}
+
+impl Aovs {
+    /// Records the geometric passes for the first surface hit along a camera ray.
+    pub fn record_first_hit(&mut self, ray: &Ray, hit_record: &HitRecord) {
+        self.albedo = hit_record.material.albedo(hit_record);
+        self.normal = hit_record.normal;
+        self.depth = hit_record.t * ray.direction.length();
+        self.alpha = 1.0;
+    }
+
+    /// Adds radiance that reached the camera after `bounces` scattering events to the
+    /// matching lighting pass. `specular` tells whether the first of those bounces was
+    /// a perfectly specular one.
+    pub fn add_lighting(&mut self, radiance: Color, bounces: i32, specular: bool) {
+        match (bounces, specular) {
+            (0, _) => self.emission += radiance,
+            (_, true) => self.specular += radiance,
+            (1, false) => self.direct_diffuse += radiance,
+            (_, false) => self.indirect_diffuse += radiance,
+        }
+    }
+}
+
+/// Per-pixel sums of `Aovs` over a whole image.
+pub struct AovBuffer {
+    width: usize,
+    height: usize,
+    beauty: Vec<Color>,
+    pixels: Vec<Aovs>,
+}
+
+impl AovBuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            beauty: vec![Color::zero(); width * height],
+            pixels: vec![Aovs::default(); width * height],
+        }
+    }
+
+    /// Accumulates one sample into pixel (`i`, `j`), counted from the top left.
+    pub fn add_sample(&mut self, i: usize, j: usize, beauty: Color, aovs: &Aovs) {
+        let index = j * self.width + i;
+        self.beauty[index] += beauty;
+
+        let pixel = &mut self.pixels[index];
+        pixel.albedo += aovs.albedo;
+        pixel.normal += aovs.normal;
+        pixel.depth += aovs.depth;
+        pixel.alpha += aovs.alpha;
+        pixel.direct_diffuse += aovs.direct_diffuse;
+        pixel.indirect_diffuse += aovs.indirect_diffuse;
+        pixel.specular += aovs.specular;
+        pixel.emission += aovs.emission;
+    }
+
+    /// Writes the beauty image and every pass, averaged over `samples_per_pixel`, to a
+    /// single OpenEXR file. The beauty image goes in the default `R`, `G`, `B` and `A`
+    /// channels and each pass in its own `pass.R` style channel group, which
+    /// compositors show as separate layers. Depth is averaged over the samples that hit
+    /// something, so silhouette pixels are not pulled towards zero.
+    pub fn write_exr(&self, path: impl AsRef<Path>, samples_per_pixel: i32) -> Result<()> {
+        let scale = 1.0 / samples_per_pixel as f64;
+        let mut channels = Vec::new();
+        let mut add_channel = |name: &str, value: &dyn Fn(usize) -> f64| {
+            let samples = (0..self.pixels.len()).map(|i| value(i) as f32).collect();
+            channels.push(AnyChannel::new(name, FlatSamples::F32(samples)));
+        };
+
+        let mut add_color = |prefix: &str, color: &dyn Fn(usize) -> Color| {
+            for (axis, suffix) in ["R", "G", "B"].iter().enumerate() {
+                let name = if prefix.is_empty() {
+                    suffix.to_string()
+                } else {
+                    format!("{prefix}.{suffix}")
+                };
+                add_channel(&name, &|i| color(i)[axis] * scale);
+            }
+        };
+        add_color("", &|i| self.beauty[i]);
+        add_color("albedo", &|i| self.pixels[i].albedo);
+        add_color("normal", &|i| self.pixels[i].normal);
+        add_color("direct_diffuse", &|i| self.pixels[i].direct_diffuse);
+        add_color("indirect_diffuse", &|i| self.pixels[i].indirect_diffuse);
+        add_color("specular", &|i| self.pixels[i].specular);
+        add_color("emission", &|i| self.pixels[i].emission);
+
+        add_channel("A", &|i| self.pixels[i].alpha * scale);
+        add_channel("depth.Z", &|i| {
+            let pixel = &self.pixels[i];
+            if pixel.alpha > 0.0 {
+                pixel.depth / pixel.alpha
+            } else {
+                0.0
+            }
+        });
+
+        let layer = Layer::new(
+            (self.width, self.height),
+            LayerAttributes::default(),
+            Encoding::FAST_LOSSLESS,
+            AnyChannels::sort(SmallVec::from_vec(channels)),
+        );
+        Image::from_layer(layer).write().to_file(path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_exr_channels() {
+        let mut buffer = AovBuffer::new(2, 1);
+        let aovs = Aovs {
+            albedo: Color::new(0.5, 0.5, 0.5),
+            depth: 3.0,
+            alpha: 1.0,
+            direct_diffuse: Color::new(1.0, 0.0, 0.0),
+            ..Aovs::default()
+        };
+        buffer.add_sample(0, 0, Color::new(1.0, 0.0, 0.0), &aovs);
+        buffer.add_sample(0, 0, Color::new(1.0, 0.0, 0.0), &aovs);
+
+        let path = std::env::temp_dir().join("raytracer_test_aovs.exr");
+        buffer.write_exr(&path, 2).unwrap();
+        let image = read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .all_channels()
+            .first_valid_layer()
+            .all_attributes()
+            .from_file(&path)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let channels = &image.layer_data.channel_data.list;
+        let mut names: Vec<String> = channels.iter().map(|c| c.name.to_string()).collect();
+        names.sort();
+        let mut expected: Vec<String> = ["R", "G", "B", "A", "depth.Z"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        for pass in [
+            "albedo",
+            "normal",
+            "direct_diffuse",
+            "indirect_diffuse",
+            "specular",
+            "emission",
+        ] {
+            for suffix in ["R", "G", "B"] {
+                expected.push(format!("{pass}.{suffix}"));
+            }
+        }
+        expected.sort();
+        assert_eq!(names, expected);
+
+        let value = |name: &str, i: usize| {
+            let channel = channels
+                .iter()
+                .find(|c| c.name.to_string() == name)
+                .unwrap();
+            channel.sample_data.value_by_flat_index(i).to_f32()
+        };
+        assert_eq!(value("R", 0), 1.0);
+        assert_eq!(value("direct_diffuse.R", 0), 1.0);
+        assert_eq!(value("depth.Z", 0), 3.0);
+        assert_eq!((value("A", 0), value("A", 1)), (1.0, 0.0));
+    }
+}
@@ -1,30 +1,35 @@
-use std::io;
+use std::{f64::consts::PI, io, path::PathBuf};
 
 use anyhow::Result;
 use log::info;
 
 use crate::{
-    degrees_to_radians, random_f64_range, random_in_unit_disk, write_color, Hittable, Interval,
-    Ray, Vec3,
+    degrees_to_radians, random_f64_range, random_in_unit_disk, write_color, AovBuffer, Aovs, Film,
+    Integrator, Point3, Ray, Scene, Vec3,
 };
 
 pub struct Camera {
     pub aspect_radio: f64,      // Ratio of image width over height
     pub image_width: f64,       // Rendered image width in pixel count
     pub samples_per_pixel: i32, // Count of random samples for each pixel
-    pub max_depth: i32,         // Maximum number of ray bounces into scene
     pub vfov: f64,              // Vertical view angle (field of view)
     pub look_from: Vec3,        //  Point camera is looking from
     pub look_at: Vec3,          //  Point camera is looking at
     pub vup: Vec3,              // Camera-relative "up" direction
     pub defocus_angle: f64,     // Variation angle of rays through each pixel
     pub focus_dist: f64,        // Distance from camera lookfrom point to plane of perfect focus
-    image_height: f64,          // Rendered image height
-    center: Vec3,               // Camera center
-    pixel00_loc: Vec3,          // Location of pixel 0, 0
-    pixel_delta_u: Vec3,        // Offset to pixel to the right
-    pixel_delta_v: Vec3,        // Offset to pixel below
-    pixel_samples_scale: f64,   // Color scale factor for a sum of pixel samples
+    pub shutter_open: f64,      // Time at which the shutter opens
+    pub shutter_close: f64,     // Time at which the shutter closes
+    // OpenEXR file to also write the beauty image and auxiliary passes to
+    pub aov_output: Option<PathBuf>,
+    image_height: f64,        // Rendered image height
+    center: Vec3,             // Camera center
+    pixel00_loc: Vec3,        // Location of pixel 0, 0
+    pixel_delta_u: Vec3,      // Offset to pixel to the right
+    pixel_delta_v: Vec3,      // Offset to pixel below
+    pixel_samples_scale: f64, // Color scale factor for a sum of pixel samples
+    film_area: f64,           // Viewport area scaled to unit distance from the camera
+    lens_area: f64,           // Area of the defocus disk, or 1 for a pinhole
     // Camera frame basis vectors
     u: Vec3,
     v: Vec3,
@@ -40,19 +45,23 @@ impl Default for Camera {
             aspect_radio: 1.0,
             image_width: 100.0,
             samples_per_pixel: 10,
-            max_depth: 10,
             vfov: 90.0,
             look_from: Vec3::new(0.0, 0.0, 0.0),
             look_at: Vec3::new(0.0, 0.0, -1.0),
             vup: Vec3::new(0.0, 1.0, 0.0),
             defocus_angle: 0.0,
             focus_dist: 10.0,
+            shutter_open: 0.0,
+            shutter_close: 1.0,
+            aov_output: None,
             image_height: Default::default(),
             center: Default::default(),
             pixel00_loc: Default::default(),
             pixel_delta_u: Default::default(),
             pixel_delta_v: Default::default(),
             pixel_samples_scale: Default::default(),
+            film_area: Default::default(),
+            lens_area: Default::default(),
             u: Default::default(),
             v: Default::default(),
             w: Default::default(),
@@ -63,29 +72,67 @@ impl Default for Camera {
 }
 
 impl Camera {
-    pub fn render(&mut self, world: &dyn Hittable) -> Result<()> {
+    /// Renders `scene` to stdout as a PPM image, estimating each camera ray's color
+    /// with `integrator`. If `aov_output` is set, the linear beauty image and the
+    /// auxiliary passes are also written there as a multi-layer OpenEXR file.
+    pub fn render(&mut self, scene: &Scene, integrator: &dyn Integrator) -> Result<()> {
         self.initialize();
+        let (width, height) = (self.image_width as usize, self.image_height as usize);
+        let mut aov_buffer = self
+            .aov_output
+            .as_ref()
+            .map(|_| AovBuffer::new(width, height));
 
         info!("Start Render.");
 
-        print!("P3\n{} {}\n255\n", self.image_width, self.image_height);
-        for j in 0..self.image_height as i32 {
-            info!("Scanlines remaining: {} ", (self.image_height as i32 - j));
-            for i in 0..self.image_width as i32 {
+        let mut film = Film::new(width, height);
+        let mut pixels = Vec::with_capacity(width * height);
+        for j in 0..height {
+            info!("Scanlines remaining: {} ", (height - j));
+            for i in 0..width {
                 let mut pixel_color = Vec3::zero();
                 for _ in 0..self.samples_per_pixel {
-                    let ray = self.get_ray(i, j);
-                    pixel_color += self.ray_color(&ray, world, self.max_depth);
+                    let ray = self.get_ray(i as i32, j as i32);
+                    match &mut aov_buffer {
+                        Some(buffer) => {
+                            let mut aovs = Aovs::default();
+                            let color =
+                                integrator.li_film_aovs(&ray, scene, self, &mut film, &mut aovs);
+                            buffer.add_sample(i, j, color, &aovs);
+                            pixel_color += color;
+                        }
+                        None => pixel_color += integrator.li_film(&ray, scene, self, &mut film),
+                    }
                 }
+                pixels.push(pixel_color);
+            }
+        }
 
-                write_color(&mut io::stdout(), pixel_color * self.pixel_samples_scale)?;
+        // Splats can land on any pixel, so the image is only written once it is complete.
+        print!("P3\n{} {}\n255\n", width, height);
+        for (index, pixel_color) in pixels.into_iter().enumerate() {
+            let splat = film.get(index % width, index / width);
+            write_color(
+                &mut io::stdout(),
+                (pixel_color + splat) * self.pixel_samples_scale,
+            )?;
+        }
+
+        if let (Some(path), Some(buffer)) = (&self.aov_output, &mut aov_buffer) {
+            for j in 0..height {
+                for i in 0..width {
+                    let lighting = film.lighting(i, j);
+                    buffer.add_sample(i, j, film.get(i, j), lighting);
+                }
             }
+            info!("Writing AOVs to {}.", path.display());
+            buffer.write_exr(path, self.samples_per_pixel)?;
         }
         info!("Done.");
         Ok(())
     }
 
-    fn initialize(&mut self) {
+    pub(crate) fn initialize(&mut self) {
         // Calculate the image height, and ensure that it's at least 1.
         self.image_height = self.image_width / self.aspect_radio;
         self.image_height = if self.image_height < 1.0 {
@@ -125,29 +172,70 @@ impl Camera {
         let defocus_radius = self.focus_dist * degrees_to_radians(self.defocus_angle / 2.0).tan();
         self.defocus_disk_u = self.u * defocus_radius;
         self.defocus_disk_v = self.v * defocus_radius;
+
+        self.film_area = viewport_width * viewport_height / (self.focus_dist * self.focus_dist);
+        self.lens_area = if self.defocus_angle <= 0.0 {
+            1.0
+        } else {
+            PI * defocus_radius * defocus_radius
+        };
     }
 
-    fn ray_color(&self, ray: &Ray, world: &dyn Hittable, depth: i32) -> Vec3 {
-        // If we've exceeded the ray bounce limit, no more light is gathered.
-        if depth <= 0 {
-            return Vec3::zero();
+    /// The pixel that a camera ray leaving the lens at `origin` along `direction` belongs
+    /// to, if it lies on the image at all.
+    pub fn raster(&self, origin: Point3, direction: Vec3) -> Option<(usize, usize)> {
+        let direction = direction.unit();
+        let cos_theta = direction.dot(-self.w);
+        if cos_theta <= 0.0 {
+            return None;
         }
 
-        if let Some(hr) = world.hit(ray, Interval::new(0.001, f64::INFINITY)) {
-            if let Some(sr) = hr.material.scatter(ray, &hr) {
-                return sr.attenuation * self.ray_color(&sr.scatter_ray, world, depth - 1);
-            }
-            return Vec3::zero();
+        // Every ray through a pixel passes through the same point on the focus plane.
+        let focus_point = origin + direction * (self.focus_dist / cos_theta);
+        let upper_left = self.pixel00_loc - (self.pixel_delta_u + self.pixel_delta_v) * 0.5;
+        let offset = focus_point - upper_left;
+        let x = offset.dot(self.pixel_delta_u) / self.pixel_delta_u.length_squard();
+        let y = offset.dot(self.pixel_delta_v) / self.pixel_delta_v.length_squard();
+        if x < 0.0 || y < 0.0 || x >= self.image_width || y >= self.image_height {
+            return None;
         }
+        Some((x as usize, y as usize))
+    }
+
+    /// Solid-angle density with which `get_ray` produces `direction` from the lens
+    /// point `origin`, over the whole image.
+    pub fn direction_pdf(&self, origin: Point3, direction: Vec3) -> f64 {
+        if self.raster(origin, direction).is_none() {
+            return 0.0;
+        }
+        let cos_theta = direction.unit().dot(-self.w);
+        1.0 / (self.film_area * cos_theta.powi(3))
+    }
+
+    /// Picks a point on the lens to connect `p` to, for tracing light towards the
+    /// camera. Returns the lens point, the pixel the connection lands in, and the
+    /// camera's importance for it divided by the density of the choice, as seen from
+    /// `p`.
+    pub fn sample_importance(&self, p: Point3) -> Option<(Point3, (usize, usize), f64)> {
+        let origin = if self.defocus_angle <= 0.0 {
+            self.center
+        } else {
+            self.defocus_disk_sample()
+        };
+        let direction = p - origin;
+        let pixel = self.raster(origin, direction)?;
 
-        let unit_directionection = ray.direction.unit();
-        let a = 0.5 * (unit_directionection.y + 1.0);
-        Vec3::ones() * (1.0 - a) + Vec3::new(0.5, 0.7, 1.0) * a
+        // The importance 1 / (A lens_area cos^4) over the density
+        // distance^2 / (cos lens_area) of picking this lens point.
+        let cos_theta = direction.unit().dot(-self.w);
+        let weight = 1.0 / (self.film_area * cos_theta.powi(3) * direction.length_squard());
+        Some((origin, pixel, weight))
     }
 
-    fn get_ray(&self, i: i32, j: i32) -> Ray {
-        // Construct a camera ray originating from the origin and directed at randomly sampled
-        // point around the pixel location i, j.
+    pub(crate) fn get_ray(&self, i: i32, j: i32) -> Ray {
+        // Construct a camera ray originating from the defocus disk and directed at a randomly
+        // sampled point around the pixel location i, j, at a random time while the shutter
+        // is open.
         let offset = self.sample_square();
         let pixel_sample = self.pixel00_loc
             + ((i as f64 + offset.x) * self.pixel_delta_u)
@@ -159,7 +247,12 @@ impl Camera {
             self.defocus_disk_sample()
         };
         let ray_direction = pixel_sample - ray_origin;
-        Ray::new(ray_origin, ray_direction)
+        let ray_time = if self.shutter_close > self.shutter_open {
+            random_f64_range(self.shutter_open, self.shutter_close)
+        } else {
+            self.shutter_open
+        };
+        Ray::with_time(ray_origin, ray_direction, ray_time)
     }
 
     fn defocus_disk_sample(&self) -> Vec3 {
@@ -177,3 +270,72 @@ impl Camera {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::random_on_hemisphere;
+
+    fn camera(defocus_angle: f64) -> Camera {
+        let mut camera = Camera {
+            aspect_radio: 2.0,
+            image_width: 16.0,
+            vfov: 90.0,
+            look_from: Vec3::new(0.0, 1.0, 2.0),
+            look_at: Vec3::new(0.0, 0.0, 0.0),
+            defocus_angle,
+            focus_dist: 3.0,
+            ..Default::default()
+        };
+        camera.initialize();
+        camera
+    }
+
+    #[test]
+    fn test_raster_inverts_get_ray() {
+        for defocus_angle in [0.0, 5.0] {
+            let camera = camera(defocus_angle);
+            for (i, j) in [(0, 0), (15, 7), (3, 5), (8, 0)] {
+                for _ in 0..20 {
+                    let ray = camera.get_ray(i as i32, j as i32);
+                    assert_eq!(camera.raster(ray.origin, ray.direction), Some((i, j)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_importance_matches_direction_pdf() {
+        let camera = camera(0.0);
+        for (i, j) in [(0, 0), (15, 7), (3, 5)] {
+            let ray = camera.get_ray(i, j);
+            let p = ray.at(2.5);
+            let (lens, pixel, weight) = camera.sample_importance(p).unwrap();
+            assert_eq!(lens, ray.origin);
+            assert_eq!(pixel, (i as usize, j as usize));
+
+            let direction = p - lens;
+            let expected = camera.direction_pdf(lens, direction) / direction.length_squard();
+            assert!((weight - expected).abs() <= 1e-12 * expected);
+        }
+        // Points behind the camera are never seen.
+        assert!(camera.sample_importance(Vec3::new(0.0, 2.0, 4.0)).is_none());
+    }
+
+    #[test]
+    fn test_direction_pdf_integrates_to_one() {
+        let camera = camera(0.0);
+        let n = 200_000;
+        let forward = (camera.look_at - camera.look_from).unit();
+        let integral = (0..n)
+            .map(|_| {
+                let direction = random_on_hemisphere(forward);
+                camera.direction_pdf(camera.look_from, direction) * 2.0 * PI
+            })
+            .sum::<f64>()
+            / n as f64;
+        assert!((integral - 1.0).abs() < 0.02, "{integral}");
+    }
+}
@@ -0,0 +1,94 @@
+use crate::{Color, Point3, Vec3};
+
+/// A light with no area. Nothing can hit it, so it only contributes through explicit
+/// shadow rays, and the shadows it casts are hard.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeltaLight {
+    /// Emits `intensity` equally in every direction from `position`, falling off with
+    /// the square of the distance.
+    Point { position: Point3, intensity: Color },
+    /// A light infinitely far away, such as the sun. Light travels along `direction`
+    /// and arrives with `irradiance` on surfaces facing it.
+    Directional { direction: Vec3, irradiance: Color },
+}
+
+impl DeltaLight {
+    pub fn point(position: Point3, intensity: Color) -> Self {
+        Self::Point {
+            position,
+            intensity,
+        }
+    }
+
+    pub fn directional(direction: Vec3, irradiance: Color) -> Self {
+        Self::Directional {
+            direction: direction.unit(),
+            irradiance,
+        }
+    }
+
+    /// Unit direction from `p` towards the light, the distance to it, and the
+    /// irradiance it delivers at `p` on a surface facing it. A point light sitting
+    /// exactly on `p` has no direction and delivers nothing.
+    pub fn illuminate(&self, p: Point3) -> (Vec3, f64, Color) {
+        match *self {
+            Self::Point {
+                position,
+                intensity,
+            } => {
+                let to_light = position - p;
+                let distance = to_light.length();
+                if distance == 0.0 {
+                    return (Vec3::new(0.0, 1.0, 0.0), 0.0, Color::zero());
+                }
+                (
+                    to_light / distance,
+                    distance,
+                    intensity / (distance * distance),
+                )
+            }
+            Self::Directional {
+                direction,
+                irradiance,
+            } => (-direction, f64::INFINITY, irradiance),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_light_falls_off_with_square_of_distance() {
+        let light = DeltaLight::point(Vec3::new(0.0, 2.0, 0.0), Color::new(8.0, 8.0, 8.0));
+
+        let (direction, distance, irradiance) = light.illuminate(Vec3::zero());
+        assert_eq!(direction, Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(distance, 2.0);
+        assert_eq!(irradiance, Color::new(2.0, 2.0, 2.0));
+
+        // Twice as far away, a quarter of the light arrives.
+        let (_, distance, irradiance) = light.illuminate(Vec3::new(0.0, -2.0, 0.0));
+        assert_eq!(distance, 4.0);
+        assert_eq!(irradiance, Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn test_point_light_at_shading_point() {
+        let light = DeltaLight::point(Vec3::new(1.0, 2.0, 3.0), Color::ones());
+        let (direction, distance, irradiance) = light.illuminate(Vec3::new(1.0, 2.0, 3.0));
+        assert!(direction.length().is_finite());
+        assert_eq!(distance, 0.0);
+        assert_eq!(irradiance, Color::zero());
+    }
+
+    #[test]
+    fn test_directional_light() {
+        let light = DeltaLight::directional(Vec3::new(0.0, -2.0, 0.0), Color::ones());
+        let (direction, distance, irradiance) = light.illuminate(Vec3::new(5.0, 0.0, -7.0));
+        assert_eq!(direction, Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(distance, f64::INFINITY);
+        assert_eq!(irradiance, Color::ones());
+    }
+}
@@ -0,0 +1,209 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{Aabb, Perlin, Point3, Vec3};
+
+/// A scalar field sampled on a regular voxel grid spanning `bounds`, read back with
+/// trilinear interpolation. Used for densities, temperatures and emission strengths.
+pub struct DensityGrid {
+    pub resolution: [usize; 3],
+    pub bounds: Aabb,
+    pub data: Vec<f64>, // x varies fastest, then y, then z
+    max_value: f64,
+}
+
+impl DensityGrid {
+    pub fn new(resolution: [usize; 3], bounds: Aabb, data: Vec<f64>) -> Self {
+        assert_eq!(
+            data.len(),
+            resolution[0] * resolution[1] * resolution[2],
+            "voxel count mismatch"
+        );
+        let max_value = data.iter().cloned().fold(0.0, f64::max);
+        Self {
+            resolution,
+            bounds,
+            data,
+            max_value,
+        }
+    }
+
+    /// Fills the grid by evaluating `f` at each voxel center.
+    pub fn from_fn(resolution: [usize; 3], bounds: Aabb, f: impl Fn(Point3) -> f64) -> Self {
+        let [nx, ny, nz] = resolution;
+        let mut data = Vec::with_capacity(nx * ny * nz);
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    let local = Vec3::new(
+                        (x as f64 + 0.5) / nx as f64,
+                        (y as f64 + 0.5) / ny as f64,
+                        (z as f64 + 0.5) / nz as f64,
+                    );
+                    data.push(f(Self::local_to_world(&bounds, local)).max(0.0));
+                }
+            }
+        }
+        Self::new(resolution, bounds, data)
+    }
+
+    /// A puffy cloud: turbulent noise at `frequency` cycles per unit length, fading out
+    /// towards the boundary of the inscribed ellipsoid.
+    pub fn from_noise(resolution: [usize; 3], bounds: Aabb, frequency: f64) -> Self {
+        let perlin = Perlin::new();
+        let center = Self::local_to_world(&bounds, Vec3::new(0.5, 0.5, 0.5));
+        let half = Vec3::new(bounds.x.size(), bounds.y.size(), bounds.z.size()) / 2.0;
+        Self::from_fn(resolution, bounds, |p| {
+            let d = p - center;
+            let r = Vec3::new(d.x / half.x, d.y / half.y, d.z / half.z).length();
+            let falloff = (1.0 - r).clamp(0.0, 1.0);
+            (perlin.turb(p * frequency, 5) * 2.0 * falloff - 0.1).max(0.0)
+        })
+    }
+
+    /// Loads a grid from the Mitsuba `.vol` format (single-channel float32 data).
+    pub fn load_vol(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        if bytes.len() < 48 || &bytes[0..3] != b"VOL" || bytes[3] != 3 {
+            bail!("{} is not a version 3 .vol file", path.display());
+        }
+
+        let int = |i: usize| i32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        let float = |i: usize| f32::from_le_bytes(bytes[i..i + 4].try_into().unwrap()) as f64;
+
+        let encoding = int(4);
+        let resolution = [int(8) as usize, int(12) as usize, int(16) as usize];
+        let channels = int(20) as usize;
+        if encoding != 1 {
+            bail!("unsupported .vol encoding {} (only float32)", encoding);
+        }
+        if channels != 1 {
+            bail!("unsupported .vol channel count {} (only 1)", channels);
+        }
+
+        let bounds = Aabb::from_points(
+            Vec3::new(float(24), float(28), float(32)),
+            Vec3::new(float(36), float(40), float(44)),
+        );
+        let count = resolution[0] * resolution[1] * resolution[2];
+        if bytes.len() < 48 + count * 4 {
+            bail!("truncated voxel data in {}", path.display());
+        }
+        let data = (0..count).map(|i| float(48 + i * 4)).collect();
+
+        Ok(Self::new(resolution, bounds, data))
+    }
+
+    /// Loads headerless little-endian float32 voxels, x varying fastest.
+    pub fn load_raw(path: impl AsRef<Path>, resolution: [usize; 3], bounds: Aabb) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let count = resolution[0] * resolution[1] * resolution[2];
+        if bytes.len() != count * 4 {
+            bail!(
+                "{} holds {} bytes, expected {} for a {:?} float32 grid",
+                path.display(),
+                bytes.len(),
+                count * 4,
+                resolution
+            );
+        }
+        let data = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()) as f64)
+            .collect();
+        Ok(Self::new(resolution, bounds, data))
+    }
+
+    pub fn max_value(&self) -> f64 {
+        self.max_value
+    }
+
+    /// Trilinearly interpolated value at `p`; zero outside the bounds.
+    pub fn value(&self, p: Point3) -> f64 {
+        let b = &self.bounds;
+        if !(b.x.contains(p.x) && b.y.contains(p.y) && b.z.contains(p.z)) {
+            return 0.0;
+        }
+
+        // Continuous voxel coordinates, with voxel centers at integer + 0.5.
+        let local = [
+            (p.x - b.x.min) / b.x.size() * self.resolution[0] as f64 - 0.5,
+            (p.y - b.y.min) / b.y.size() * self.resolution[1] as f64 - 0.5,
+            (p.z - b.z.min) / b.z.size() * self.resolution[2] as f64 - 0.5,
+        ];
+
+        let mut i0 = [0; 3];
+        let mut i1 = [0; 3];
+        let mut f = [0.0; 3];
+        for axis in 0..3 {
+            let max = self.resolution[axis] as f64 - 1.0;
+            let c = local[axis].clamp(0.0, max);
+            i0[axis] = c.floor() as usize;
+            i1[axis] = (i0[axis] + 1).min(self.resolution[axis] - 1);
+            f[axis] = c - c.floor();
+        }
+
+        let mut accum = 0.0;
+        for (dz, wz) in [(i0[2], 1.0 - f[2]), (i1[2], f[2])] {
+            for (dy, wy) in [(i0[1], 1.0 - f[1]), (i1[1], f[1])] {
+                for (dx, wx) in [(i0[0], 1.0 - f[0]), (i1[0], f[0])] {
+                    accum += wx * wy * wz * self.voxel(dx, dy, dz);
+                }
+            }
+        }
+        accum
+    }
+
+    fn voxel(&self, x: usize, y: usize, z: usize) -> f64 {
+        self.data[(z * self.resolution[1] + y) * self.resolution[0] + x]
+    }
+
+    fn local_to_world(bounds: &Aabb, local: Vec3) -> Point3 {
+        Point3::new(
+            bounds.x.min + local.x * bounds.x.size(),
+            bounds.y.min + local.y * bounds.y.size(),
+            bounds.z.min + local.z * bounds.z.size(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_bounds() -> Aabb {
+        Aabb::from_points(Vec3::zero(), Vec3::ones())
+    }
+
+    #[test]
+    fn test_trilinear() {
+        let grid = DensityGrid::new([2, 1, 1], unit_bounds(), vec![0.0, 1.0]);
+        assert_eq!(grid.value(Point3::new(0.25, 0.5, 0.5)), 0.0);
+        assert_eq!(grid.value(Point3::new(0.5, 0.5, 0.5)), 0.5);
+        assert_eq!(grid.value(Point3::new(0.75, 0.5, 0.5)), 1.0);
+        assert_eq!(grid.value(Point3::new(1.5, 0.5, 0.5)), 0.0);
+        assert_eq!(grid.max_value(), 1.0);
+    }
+
+    #[test]
+    fn test_load_vol() {
+        let path = std::env::temp_dir().join("raytracer_test_grid.vol");
+        let mut bytes = b"VOL\x03".to_vec();
+        for value in [1i32, 2, 1, 1, 1] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [0f32, 0.0, 0.0, 2.0, 1.0, 1.0, 0.25, 0.75] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        std::fs::write(&path, bytes).unwrap();
+
+        let grid = DensityGrid::load_vol(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(grid.resolution, [2, 1, 1]);
+        assert_eq!(grid.data, vec![0.25, 0.75]);
+        assert_eq!(grid.bounds.x.max, 2.0);
+    }
+}
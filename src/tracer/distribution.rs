@@ -0,0 +1,172 @@
+/// A piecewise-constant 1D distribution over [0, 1) built from a tabulated function.
+pub struct Distribution1D {
+    pub func: Vec<f64>,
+    pub cdf: Vec<f64>,
+    pub func_int: f64,
+}
+
+impl Distribution1D {
+    /// Panics if `func` is empty.
+    pub fn new(func: &[f64]) -> Self {
+        assert!(!func.is_empty(), "a distribution needs at least one value");
+        let n = func.len();
+        let func: Vec<f64> = func.iter().map(|f| f.abs()).collect();
+
+        // Integrate the step function; the cdf has one more entry than the function.
+        let mut cdf = vec![0.0; n + 1];
+        for i in 1..=n {
+            cdf[i] = cdf[i - 1] + func[i - 1] / n as f64;
+        }
+
+        let func_int = cdf[n];
+        if func_int == 0.0 {
+            // Fall back to a uniform distribution if the function is zero everywhere.
+            for (i, c) in cdf.iter_mut().enumerate().skip(1) {
+                *c = i as f64 / n as f64;
+            }
+        } else {
+            for c in cdf.iter_mut().skip(1) {
+                *c /= func_int;
+            }
+        }
+
+        Self {
+            func,
+            cdf,
+            func_int,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.func.len()
+    }
+
+    /// Maps a uniform sample `u` to a point in [0, 1).
+    /// Returns the point, its density and the index of the segment it fell in.
+    pub fn sample_continuous(&self, u: f64) -> (f64, f64, usize) {
+        // Find the last cdf entry that is <= u.
+        let offset = self
+            .cdf
+            .partition_point(|&c| c <= u)
+            .saturating_sub(1)
+            .min(self.count() - 1);
+
+        let mut du = u - self.cdf[offset];
+        let width = self.cdf[offset + 1] - self.cdf[offset];
+        if width > 0.0 {
+            du /= width;
+        }
+
+        let pdf = self.pdf_at(offset);
+        ((offset as f64 + du) / self.count() as f64, pdf, offset)
+    }
+
+    /// Density of the segment containing `x`.
+    pub fn pdf(&self, x: f64) -> f64 {
+        let offset = ((x * self.count() as f64) as usize).min(self.count() - 1);
+        self.pdf_at(offset)
+    }
+
+    fn pdf_at(&self, offset: usize) -> f64 {
+        if self.func_int > 0.0 {
+            self.func[offset] / self.func_int
+        } else {
+            1.0
+        }
+    }
+}
+
+/// A piecewise-constant 2D distribution over [0, 1)^2, sampled by choosing a row
+/// from the marginal and then a column from that row's conditional.
+pub struct Distribution2D {
+    pub conditional: Vec<Distribution1D>,
+    pub marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    /// `func` is laid out row by row, `width` entries per row. Panics if either
+    /// dimension is zero or `func` is too short for them.
+    pub fn new(func: &[f64], width: usize, height: usize) -> Self {
+        assert!(
+            width > 0 && height > 0,
+            "a distribution needs at least one value"
+        );
+        assert!(func.len() >= width * height, "function too short");
+        let conditional: Vec<Distribution1D> = func
+            .chunks(width)
+            .take(height)
+            .map(Distribution1D::new)
+            .collect();
+        let marginal_func: Vec<f64> = conditional.iter().map(|c| c.func_int).collect();
+        let marginal = Distribution1D::new(&marginal_func);
+
+        Self {
+            conditional,
+            marginal,
+        }
+    }
+
+    /// Maps two uniform samples to a point (x, y) in [0, 1)^2 and its density.
+    pub fn sample_continuous(&self, u0: f64, u1: f64) -> (f64, f64, f64) {
+        let (y, pdf_y, row) = self.marginal.sample_continuous(u1);
+        let (x, pdf_x, _) = self.conditional[row].sample_continuous(u0);
+        (x, y, pdf_x * pdf_y)
+    }
+
+    pub fn pdf(&self, x: f64, y: f64) -> f64 {
+        let height = self.marginal.count();
+        let row = ((y * height as f64) as usize).min(height - 1);
+        if self.marginal.func_int == 0.0 {
+            return 1.0;
+        }
+        self.conditional[row].pdf(x) * self.conditional[row].func_int / self.marginal.func_int
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_1d_cdf() {
+        let d = Distribution1D::new(&[1.0, 3.0]);
+        assert_eq!(d.cdf, vec![0.0, 0.25, 1.0]);
+        assert_eq!(d.func_int, 2.0);
+        assert_eq!(d.pdf(0.1), 0.5);
+        assert_eq!(d.pdf(0.9), 1.5);
+    }
+
+    #[test]
+    fn test_distribution_1d_sample() {
+        let d = Distribution1D::new(&[1.0, 3.0]);
+        let (x, pdf, offset) = d.sample_continuous(0.125);
+        assert_eq!((x, pdf, offset), (0.25, 0.5, 0));
+
+        let (x, pdf, offset) = d.sample_continuous(0.625);
+        assert_eq!((x, pdf, offset), (0.75, 1.5, 1));
+    }
+
+    #[test]
+    fn test_distribution_1d_zero() {
+        let d = Distribution1D::new(&[0.0, 0.0, 0.0, 0.0]);
+        let (x, pdf, _) = d.sample_continuous(0.5);
+        assert_eq!((x, pdf), (0.5, 1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_distribution_2d_zero_width() {
+        Distribution2D::new(&[], 0, 4);
+    }
+
+    #[test]
+    fn test_distribution_2d_pdf_matches_sample() {
+        let func = [1.0, 2.0, 0.0, 5.0, 1.0, 1.0];
+        let d = Distribution2D::new(&func, 3, 2);
+        for &(u0, u1) in &[(0.1, 0.2), (0.7, 0.4), (0.3, 0.9), (0.95, 0.6)] {
+            let (x, y, pdf) = d.sample_continuous(u0, u1);
+            assert!((d.pdf(x, y) - pdf).abs() < 1e-12);
+            assert!(pdf > 0.0);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{rgb_to_illuminant, SampledSpectrum, SampledWavelengths, Vec3};
+
+pub trait Environment {
+    /// Radiance arriving along a ray that escaped the scene in `direction`.
+    fn value(&self, direction: Vec3) -> Vec3;
+
+    /// Spectral radiance along `direction` at the path's wavelengths. Defaults to
+    /// upsampling the RGB radiance.
+    fn value_spectrum(&self, direction: Vec3, wavelengths: &SampledWavelengths) -> SampledSpectrum {
+        rgb_to_illuminant(self.value(direction), wavelengths)
+    }
+
+    /// Draws a direction towards the environment and its solid-angle density.
+    /// Environments without an importance-sampling scheme return `None`.
+    fn sample(&self) -> Option<(Vec3, f64)> {
+        None
+    }
+
+    /// Solid-angle density with which `sample` would pick `direction`.
+    fn pdf(&self, _direction: Vec3) -> f64 {
+        0.0
+    }
+}
@@ -0,0 +1,11 @@
+use crate::{Environment, Vec3};
+
+/// An environment that contributes no light, for scenes lit only by emitters.
+#[derive(Default)]
+pub struct BlackEnvironment;
+
+impl Environment for BlackEnvironment {
+    fn value(&self, _direction: Vec3) -> Vec3 {
+        Vec3::zero()
+    }
+}
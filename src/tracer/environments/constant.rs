@@ -0,0 +1,30 @@
+use crate::{Environment, Vec3};
+
+pub struct ConstantEnvironment {
+    pub color: Vec3,
+}
+
+impl ConstantEnvironment {
+    pub fn new(color: Vec3) -> Self {
+        Self { color }
+    }
+}
+
+impl Environment for ConstantEnvironment {
+    fn value(&self, _direction: Vec3) -> Vec3 {
+        self.color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_in_every_direction() {
+        let color = Vec3::new(0.1, 0.2, 0.3);
+        let environment = ConstantEnvironment::new(color);
+        assert_eq!(environment.value(Vec3::new(0.0, 1.0, 0.0)), color);
+        assert_eq!(environment.value(Vec3::new(-4.0, -1.0, 2.0)), color);
+    }
+}
@@ -0,0 +1,327 @@
+use std::{
+    f64::consts::PI,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{degrees_to_radians, luminance, random_f64, Distribution2D, Environment, Vec3};
+
+/// An equirectangular (latitude-longitude) radiance map.
+///
+/// The center of the image looks down -z, the top row is straight up. Directions are
+/// importance-sampled proportionally to pixel luminance.
+pub struct EnvironmentMap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Vec3>, // Row-major radiance, top row first
+    pub rotation: f64,     // Rotation around the vertical axis, in degrees
+    pub intensity: f64,    // Scale applied to every lookup
+    distribution: Distribution2D,
+}
+
+impl EnvironmentMap {
+    /// Panics if the map is empty or `pixels` does not hold `width * height` values.
+    pub fn new(width: usize, height: usize, pixels: Vec<Vec3>) -> Self {
+        assert_eq!(pixels.len(), width * height, "pixel count mismatch");
+
+        // Weight each texel by sin(theta) so rows near the poles, which cover less solid
+        // angle, are not oversampled.
+        let mut func = Vec::with_capacity(width * height);
+        for row in 0..height {
+            let sin_theta = (PI * (row as f64 + 0.5) / height as f64).sin();
+            for col in 0..width {
+                func.push(luminance(pixels[row * width + col]) * sin_theta);
+            }
+        }
+        let distribution = Distribution2D::new(&func, width, height);
+
+        Self {
+            width,
+            height,
+            pixels,
+            rotation: 0.0,
+            intensity: 1.0,
+            distribution,
+        }
+    }
+
+    /// Loads a Radiance `.hdr` or OpenEXR `.exr` file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let (width, height, pixels) = match extension.as_deref() {
+            Some("hdr") => read_hdr(path)?,
+            Some("exr") => read_exr(path)?,
+            _ => bail!("unsupported environment map format: {}", path.display()),
+        };
+        if width == 0 || height == 0 {
+            bail!("{} has no pixels ({width}x{height})", path.display());
+        }
+        Ok(Self::new(width, height, pixels))
+    }
+
+    pub fn with_rotation(mut self, degrees: f64) -> Self {
+        self.rotation = degrees;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Maps a world-space direction to image coordinates in [0, 1)^2.
+    fn direction_to_uv(&self, direction: Vec3) -> (f64, f64) {
+        let d = self.rotate(direction.unit(), -self.rotation);
+        let theta = d.y.clamp(-1.0, 1.0).acos();
+        let phi = d.x.atan2(-d.z);
+        let u = 0.5 + phi / (2.0 * PI);
+        let v = theta / PI;
+        (
+            u.clamp(0.0, 1.0 - f64::EPSILON),
+            v.clamp(0.0, 1.0 - f64::EPSILON),
+        )
+    }
+
+    fn uv_to_direction(&self, u: f64, v: f64) -> Vec3 {
+        let theta = v * PI;
+        let phi = (u - 0.5) * 2.0 * PI;
+        let sin_theta = theta.sin();
+        let d = Vec3::new(sin_theta * phi.sin(), theta.cos(), -sin_theta * phi.cos());
+        self.rotate(d, self.rotation)
+    }
+
+    fn rotate(&self, d: Vec3, degrees: f64) -> Vec3 {
+        let (sin, cos) = degrees_to_radians(degrees).sin_cos();
+        Vec3::new(cos * d.x + sin * d.z, d.y, -sin * d.x + cos * d.z)
+    }
+
+    fn lookup(&self, u: f64, v: f64) -> Vec3 {
+        let col = ((u * self.width as f64) as usize).min(self.width - 1);
+        let row = ((v * self.height as f64) as usize).min(self.height - 1);
+        self.pixels[row * self.width + col]
+    }
+
+    /// Draws a direction with density proportional to the map's luminance from the
+    /// uniform samples `u0`, `u1`. Returns the direction and its solid-angle density.
+    pub fn sample_with(&self, u0: f64, u1: f64) -> Option<(Vec3, f64)> {
+        let (u, v, map_pdf) = self.distribution.sample_continuous(u0, u1);
+        let sin_theta = (v * PI).sin();
+        if map_pdf == 0.0 || sin_theta == 0.0 {
+            return None;
+        }
+        // Convert the density from image space to solid angle.
+        let pdf = map_pdf / (2.0 * PI * PI * sin_theta);
+        Some((self.uv_to_direction(u, v), pdf))
+    }
+}
+
+impl Environment for EnvironmentMap {
+    fn value(&self, direction: Vec3) -> Vec3 {
+        let (u, v) = self.direction_to_uv(direction);
+        self.intensity * self.lookup(u, v)
+    }
+
+    fn sample(&self) -> Option<(Vec3, f64)> {
+        self.sample_with(random_f64(), random_f64())
+    }
+
+    fn pdf(&self, direction: Vec3) -> f64 {
+        let (u, v) = self.direction_to_uv(direction);
+        let sin_theta = (v * PI).sin();
+        if sin_theta == 0.0 {
+            return 0.0;
+        }
+        self.distribution.pdf(u, v) / (2.0 * PI * PI * sin_theta)
+    }
+}
+
+fn read_exr(path: &Path) -> Result<(usize, usize, Vec<Vec3>)> {
+    let image = exr::prelude::read_first_rgba_layer_from_file(
+        path,
+        |resolution, _| {
+            (
+                resolution.width(),
+                vec![Vec3::zero(); resolution.width() * resolution.height()],
+            )
+        },
+        |(width, pixels), position, (r, g, b, _a): (f32, f32, f32, f32)| {
+            pixels[position.y() * *width + position.x()] = Vec3::new(r as f64, g as f64, b as f64);
+        },
+    )
+    .with_context(|| format!("failed to read {}", path.display()))?;
+
+    let size = image.layer_data.size;
+    let (_, pixels) = image.layer_data.channel_data.pixels;
+    Ok((size.width(), size.height(), pixels))
+}
+
+/// Reads a Radiance RGBE image, supporting both flat and run-length encoded scanlines.
+fn read_hdr(path: &Path) -> Result<(usize, usize, Vec<Vec3>)> {
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    if !line.starts_with("#?") {
+        bail!("{} is not a Radiance HDR file", path.display());
+    }
+
+    // Header lines run until the first blank line.
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            bail!("unexpected end of header in {}", path.display());
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some(format) = trimmed.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                bail!("unsupported HDR pixel format: {}", format);
+            }
+        }
+    }
+
+    // Only the standard "-Y height +X width" orientation is supported.
+    line.clear();
+    reader.read_line(&mut line)?;
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let (height, width) = match tokens.as_slice() {
+        ["-Y", h, "+X", w] => (h.parse::<usize>()?, w.parse::<usize>()?),
+        _ => bail!("unsupported HDR resolution line: {}", line.trim()),
+    };
+
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut pixels = Vec::with_capacity(width * height);
+    let mut pos = 0;
+    let mut scanline = vec![[0u8; 4]; width];
+    for _ in 0..height {
+        pos = read_hdr_scanline(&data, pos, &mut scanline)
+            .ok_or_else(|| anyhow!("truncated pixel data in {}", path.display()))?;
+        pixels.extend(scanline.iter().map(|&rgbe| rgbe_to_vec3(rgbe)));
+    }
+
+    Ok((width, height, pixels))
+}
+
+fn read_hdr_scanline(data: &[u8], mut pos: usize, scanline: &mut [[u8; 4]]) -> Option<usize> {
+    let width = scanline.len();
+    let header = data.get(pos..pos + 4)?;
+    let is_rle = (8..0x8000).contains(&width)
+        && header[0] == 2
+        && header[1] == 2
+        && ((header[2] as usize) << 8 | header[3] as usize) == width;
+
+    if !is_rle {
+        for pixel in scanline.iter_mut() {
+            pixel.copy_from_slice(data.get(pos..pos + 4)?);
+            pos += 4;
+        }
+        return Some(pos);
+    }
+
+    // New-style RLE stores each of the four components as its own run-length stream.
+    pos += 4;
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let count = *data.get(pos)? as usize;
+            pos += 1;
+            if count == 0 {
+                return None;
+            }
+            if count > 128 {
+                let run = count - 128;
+                let value = *data.get(pos)?;
+                pos += 1;
+                for pixel in scanline.get_mut(x..x + run)? {
+                    pixel[channel] = value;
+                }
+                x += run;
+            } else {
+                for pixel in scanline.get_mut(x..x + count)? {
+                    pixel[channel] = *data.get(pos)?;
+                    pos += 1;
+                }
+                x += count;
+            }
+        }
+    }
+    Some(pos)
+}
+
+fn rgbe_to_vec3(rgbe: [u8; 4]) -> Vec3 {
+    if rgbe[3] == 0 {
+        return Vec3::zero();
+    }
+    let f = 2f64.powi(rgbe[3] as i32 - 136);
+    Vec3::new(
+        (rgbe[0] as f64 + 0.5) * f,
+        (rgbe[1] as f64 + 0.5) * f,
+        (rgbe[2] as f64 + 0.5) * f,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_map() -> EnvironmentMap {
+        let (width, height) = (8, 4);
+        let pixels = (0..width * height)
+            .map(|i| Vec3::ones() * (1.0 + (i % 5) as f64))
+            .collect();
+        EnvironmentMap::new(width, height, pixels).with_rotation(30.0)
+    }
+
+    #[test]
+    fn test_uv_round_trip() {
+        let map = test_map();
+        let d = Vec3::new(0.3, 0.5, -0.8).unit();
+        let (u, v) = map.direction_to_uv(d);
+        assert!((map.uv_to_direction(u, v) - d).length() < 1e-9);
+    }
+
+    #[test]
+    fn test_sample_pdf_matches_lookup() {
+        let map = test_map();
+        for &(u0, u1) in &[(0.1, 0.3), (0.5, 0.5), (0.85, 0.7)] {
+            let (direction, pdf) = map.sample_with(u0, u1).unwrap();
+            assert!((map.pdf(direction) - pdf).abs() / pdf < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_read_hdr_flat() {
+        let path = std::env::temp_dir().join("raytracer_test_flat.hdr");
+        let mut bytes = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n".to_vec();
+        bytes.extend_from_slice(&[128, 64, 0, 129, 0, 0, 0, 0]);
+        std::fs::write(&path, bytes).unwrap();
+
+        let (width, height, pixels) = read_hdr(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(pixels[0], Vec3::new(128.5, 64.5, 0.5) / 128.0);
+        assert_eq!(pixels[1], Vec3::zero());
+    }
+
+    #[test]
+    fn test_load_empty_image_fails() {
+        let path = std::env::temp_dir().join("raytracer_test_empty.hdr");
+        std::fs::write(&path, b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 4 +X 0\n").unwrap();
+
+        let result = EnvironmentMap::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{Environment, Vec3};
+
+/// Vertical blend from `bottom` (straight down) to `top` (straight up).
+pub struct GradientSky {
+    pub bottom: Vec3,
+    pub top: Vec3,
+}
+
+impl GradientSky {
+    pub fn new(bottom: Vec3, top: Vec3) -> Self {
+        Self { bottom, top }
+    }
+}
+
+impl Default for GradientSky {
+    fn default() -> Self {
+        Self::new(Vec3::ones(), Vec3::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Environment for GradientSky {
+    fn value(&self, direction: Vec3) -> Vec3 {
+        let unit_direction = direction.unit();
+        let a = 0.5 * (unit_direction.y + 1.0);
+        self.bottom * (1.0 - a) + self.top * a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blend() {
+        let sky = GradientSky::new(Vec3::zero(), Vec3::ones());
+        assert_eq!(sky.value(Vec3::new(0.0, 2.0, 0.0)), Vec3::ones());
+        assert_eq!(sky.value(Vec3::new(0.0, -3.0, 0.0)), Vec3::zero());
+        assert_eq!(
+            sky.value(Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::new(0.5, 0.5, 0.5)
+        );
+    }
+}
@@ -0,0 +1,13 @@
+pub mod black;
+pub mod constant;
+pub mod environment_map;
+pub mod gradient;
+pub mod physical_sky;
+pub mod sun_sky;
+
+pub use black::*;
+pub use constant::*;
+pub use environment_map::*;
+pub use gradient::*;
+pub use physical_sky::*;
+pub use sun_sky::*;
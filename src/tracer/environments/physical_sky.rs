@@ -0,0 +1,432 @@
+use std::f64::consts::PI;
+
+use crate::{
+    degrees_to_radians, random_f64, random_uint_vector, xyz_to_linear_srgb, Environment, Onb, Vec3,
+};
+
+/// Angular radius of the sun disk as seen from the earth, in degrees.
+const SUN_ANGULAR_RADIUS: f64 = 0.2665;
+
+/// Local civil time used to place the sun for a given location.
+pub struct SolarTime {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: f64,       // Local time of day in hours, e.g. 14.5 for 14:30
+    pub utc_offset: f64, // Hours ahead of UTC, e.g. 2.0 for CEST
+}
+
+/// Analytic daylight following Preetham, Shirley and Smits, "A Practical Analytic Model
+/// for Daylight" (1999), with an attenuated sun disk and a diffuse ground plane.
+///
+/// Azimuth is measured clockwise from north; north is -z, east is +x and up is +y.
+pub struct PhysicalSky {
+    pub sun_elevation: f64, // Degrees above the horizon
+    pub sun_azimuth: f64,   // Degrees clockwise from north
+    pub turbidity: f64,     // Haziness of the atmosphere, 2 (clear) to 10 (hazy)
+    pub ground_albedo: Vec3,
+    pub intensity: f64, // Scale from the model's kcd/m^2 to scene radiance
+    sun_direction: Vec3,
+    sun_radiance: Vec3,
+    ground_radiance: Vec3,
+    perez_y: [f64; 5],
+    perez_x: [f64; 5],
+    perez_yy: [f64; 5],
+    zenith: Vec3, // Zenith (Y, x, y) divided by the Perez function at the zenith
+}
+
+impl PhysicalSky {
+    pub fn new(sun_elevation: f64, sun_azimuth: f64) -> Self {
+        let mut sky = Self {
+            sun_elevation,
+            sun_azimuth,
+            turbidity: 3.0,
+            ground_albedo: Vec3::new(0.3, 0.3, 0.3),
+            intensity: 0.06,
+            sun_direction: Vec3::zero(),
+            sun_radiance: Vec3::zero(),
+            ground_radiance: Vec3::zero(),
+            perez_y: [0.0; 5],
+            perez_x: [0.0; 5],
+            perez_yy: [0.0; 5],
+            zenith: Vec3::zero(),
+        };
+        sky.update();
+        sky
+    }
+
+    /// Places the sun for an observer at `latitude`/`longitude` (degrees, north and east
+    /// positive) at the given local time.
+    pub fn from_location(latitude: f64, longitude: f64, time: &SolarTime) -> Self {
+        let (elevation, azimuth) = solar_position(latitude, longitude, time);
+        Self::new(elevation, azimuth)
+    }
+
+    pub fn with_turbidity(mut self, turbidity: f64) -> Self {
+        self.turbidity = turbidity.clamp(1.0, 10.0);
+        self.update();
+        self
+    }
+
+    pub fn with_ground_albedo(mut self, ground_albedo: Vec3) -> Self {
+        self.ground_albedo = ground_albedo;
+        self.update();
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn sun_direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
+    fn update(&mut self) {
+        let t = self.turbidity;
+        let elevation = degrees_to_radians(self.sun_elevation);
+        let azimuth = degrees_to_radians(self.sun_azimuth);
+        self.sun_direction = Vec3::new(
+            azimuth.sin() * elevation.cos(),
+            elevation.sin(),
+            -azimuth.cos() * elevation.cos(),
+        );
+
+        // The model is only fitted for a sun above the horizon.
+        let theta_s = (PI / 2.0 - elevation).clamp(0.0, PI / 2.0 - 0.001);
+
+        self.perez_y = [
+            0.1787 * t - 1.4630,
+            -0.3554 * t + 0.4275,
+            -0.0227 * t + 5.3251,
+            0.1206 * t - 2.5771,
+            -0.0670 * t + 0.3703,
+        ];
+        self.perez_x = [
+            -0.0193 * t - 0.2592,
+            -0.0665 * t + 0.0008,
+            -0.0004 * t + 0.2125,
+            -0.0641 * t - 0.8989,
+            -0.0033 * t + 0.0452,
+        ];
+        self.perez_yy = [
+            -0.0167 * t - 0.2608,
+            -0.0950 * t + 0.0092,
+            -0.0079 * t + 0.2102,
+            -0.0441 * t - 1.6537,
+            -0.0109 * t + 0.0529,
+        ];
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let zenith_luminance = ((4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192).max(0.0);
+
+        let (th, th2, th3) = (theta_s, theta_s * theta_s, theta_s * theta_s * theta_s);
+        let t2 = t * t;
+        let zenith_x = t2 * (0.00166 * th3 - 0.00375 * th2 + 0.00209 * th)
+            + t * (-0.02903 * th3 + 0.06377 * th2 - 0.03202 * th + 0.00394)
+            + (0.11693 * th3 - 0.21196 * th2 + 0.06052 * th + 0.25886);
+        let zenith_y = t2 * (0.00275 * th3 - 0.00610 * th2 + 0.00317 * th)
+            + t * (-0.04214 * th3 + 0.08970 * th2 - 0.04153 * th + 0.00516)
+            + (0.15346 * th3 - 0.26756 * th2 + 0.06670 * th + 0.26688);
+
+        self.zenith = Vec3::new(
+            zenith_luminance / perez(&self.perez_y, 0.0, theta_s),
+            zenith_x / perez(&self.perez_x, 0.0, theta_s),
+            zenith_y / perez(&self.perez_yy, 0.0, theta_s),
+        );
+
+        self.sun_radiance = self.compute_sun_radiance(theta_s);
+        self.ground_radiance = self.compute_ground_radiance();
+    }
+
+    /// Sun disk radiance after Rayleigh and aerosol extinction along the optical path.
+    fn compute_sun_radiance(&self, theta_s: f64) -> Vec3 {
+        if self.sun_elevation < -SUN_ANGULAR_RADIUS {
+            return Vec3::zero();
+        }
+
+        // Relative optical air mass (Kasten and Young).
+        let theta_deg = theta_s.to_degrees();
+        let air_mass = 1.0 / (theta_s.cos() + 0.15 * (93.885 - theta_deg).powf(-1.253));
+        let beta = 0.04608 * self.turbidity - 0.04586;
+
+        let mut radiance = Vec3::zero();
+        for (i, lambda) in [0.65, 0.57, 0.475].into_iter().enumerate() {
+            let rayleigh = (-0.008735 * f64::powf(lambda, -4.08) * air_mass).exp();
+            let aerosol = (-beta * f64::powf(lambda, -1.3) * air_mass).exp();
+            radiance[i] = rayleigh * aerosol;
+        }
+
+        // Scale so the unattenuated sun delivers ~100 kcd/m^2 of illuminance on a surface
+        // facing it, the same units as the sky model.
+        let solid_angle = 2.0 * PI * (1.0 - degrees_to_radians(SUN_ANGULAR_RADIUS).cos());
+        radiance * (100.0 / solid_angle)
+    }
+
+    /// Radiance reflected by a diffuse ground lit by the upper hemisphere.
+    fn compute_ground_radiance(&self) -> Vec3 {
+        let (n_theta, n_phi) = (16, 32);
+        let mut irradiance = Vec3::zero();
+        for i in 0..n_theta {
+            let theta = (i as f64 + 0.5) / n_theta as f64 * PI / 2.0;
+            for j in 0..n_phi {
+                let phi = (j as f64 + 0.5) / n_phi as f64 * 2.0 * PI;
+                let d = Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+                let d_omega = theta.sin() * (PI / 2.0 / n_theta as f64) * (2.0 * PI / n_phi as f64);
+                irradiance += self.sky_radiance(d) * theta.cos() * d_omega;
+            }
+        }
+
+        let solid_angle = 2.0 * PI * (1.0 - degrees_to_radians(SUN_ANGULAR_RADIUS).cos());
+        irradiance += self.sun_radiance * solid_angle * self.sun_direction.y.max(0.0);
+
+        self.ground_albedo * irradiance / PI
+    }
+
+    /// Preetham sky radiance without the sun disk, in the model's kcd/m^2 units.
+    fn sky_radiance(&self, d: Vec3) -> Vec3 {
+        let cos_theta = d.y.max(0.001);
+        let theta = cos_theta.acos();
+        let gamma = d.dot(self.sun_direction).clamp(-1.0, 1.0).acos();
+
+        let luminance = self.zenith.x * perez(&self.perez_y, theta, gamma);
+        let x = self.zenith.y * perez(&self.perez_x, theta, gamma);
+        let y = self.zenith.z * perez(&self.perez_yy, theta, gamma);
+
+        // Convert the xyY chromaticity to XYZ.
+        let xyz = Vec3::new(x / y * luminance, luminance, (1.0 - x - y) / y * luminance);
+        let rgb = xyz_to_linear_srgb(xyz);
+        Vec3::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+    }
+
+    fn sun_cone_cos(&self) -> f64 {
+        degrees_to_radians(SUN_ANGULAR_RADIUS).cos()
+    }
+}
+
+impl Environment for PhysicalSky {
+    fn value(&self, direction: Vec3) -> Vec3 {
+        let d = direction.unit();
+        if d.y < 0.0 {
+            return self.intensity * self.ground_radiance;
+        }
+
+        let mut radiance = self.sky_radiance(d);
+        if d.dot(self.sun_direction) >= self.sun_cone_cos() {
+            radiance += self.sun_radiance;
+        }
+        self.intensity * radiance
+    }
+
+    fn sample(&self) -> Option<(Vec3, f64)> {
+        // Half the samples go to the tiny, very bright sun disk, the rest uniformly
+        // over the sphere.
+        let direction = if self.sun_direction.y > 0.0 && random_f64() < 0.5 {
+            let cos_theta = 1.0 + random_f64() * (self.sun_cone_cos() - 1.0);
+            let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+            let phi = 2.0 * PI * random_f64();
+            Onb::new(self.sun_direction).transform(Vec3::new(
+                sin_theta * phi.cos(),
+                sin_theta * phi.sin(),
+                cos_theta,
+            ))
+        } else {
+            random_uint_vector()
+        };
+        Some((direction, self.pdf(direction)))
+    }
+
+    fn pdf(&self, direction: Vec3) -> f64 {
+        let uniform = 1.0 / (4.0 * PI);
+        if self.sun_direction.y <= 0.0 {
+            return uniform;
+        }
+        let cone = if direction.unit().dot(self.sun_direction) >= self.sun_cone_cos() {
+            1.0 / (2.0 * PI * (1.0 - self.sun_cone_cos()))
+        } else {
+            0.0
+        };
+        0.5 * uniform + 0.5 * cone
+    }
+}
+
+/// Perez et al. all-weather luminance distribution.
+fn perez(coefficients: &[f64; 5], theta: f64, gamma: f64) -> f64 {
+    let [a, b, c, d, e] = *coefficients;
+    let cos_gamma = gamma.cos();
+    (1.0 + a * (b / theta.cos().max(0.001)).exp())
+        * (1.0 + c * (d * gamma).exp() + e * cos_gamma * cos_gamma)
+}
+
+/// Returns the sun's (elevation, azimuth) in degrees using the NOAA low-accuracy
+/// solar position equations. Good to about a degree, which is plenty for lighting.
+pub fn solar_position(latitude: f64, longitude: f64, time: &SolarTime) -> (f64, f64) {
+    let day_of_year = day_of_year(time.year, time.month, time.day) as f64;
+    let hour_utc = time.hour - time.utc_offset;
+    let days_in_year = if is_leap_year(time.year) {
+        366.0
+    } else {
+        365.0
+    };
+
+    // Fractional year, in radians.
+    let g = 2.0 * PI / days_in_year * (day_of_year - 1.0 + (hour_utc - 12.0) / 24.0);
+
+    let eq_time = 229.18
+        * (0.000075 + 0.001868 * g.cos()
+            - 0.032077 * g.sin()
+            - 0.014615 * (2.0 * g).cos()
+            - 0.040849 * (2.0 * g).sin());
+    let declination = 0.006918 - 0.399912 * g.cos() + 0.070257 * g.sin()
+        - 0.006758 * (2.0 * g).cos()
+        + 0.000907 * (2.0 * g).sin()
+        - 0.002697 * (3.0 * g).cos()
+        + 0.00148 * (3.0 * g).sin();
+
+    // True solar time in minutes and the hour angle in radians.
+    let true_solar_time = hour_utc * 60.0 + eq_time + 4.0 * longitude;
+    let hour_angle = degrees_to_radians(true_solar_time / 4.0 - 180.0);
+
+    let lat = degrees_to_radians(latitude);
+    let sin_elevation =
+        lat.sin() * declination.sin() + lat.cos() * declination.cos() * hour_angle.cos();
+    let elevation = sin_elevation.clamp(-1.0, 1.0).asin();
+
+    // Azimuth from south, westward positive, then shifted to clockwise from north.
+    let azimuth = hour_angle
+        .sin()
+        .atan2(hour_angle.cos() * lat.sin() - declination.tan() * lat.cos());
+
+    (
+        elevation.to_degrees(),
+        (azimuth.to_degrees() + 180.0).rem_euclid(360.0),
+    )
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn day_of_year(year: i32, month: u32, day: u32) -> u32 {
+    const CUMULATIVE_DAYS: [u32; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+    let month = month.clamp(1, 12);
+    let leap_day = if month > 2 && is_leap_year(year) {
+        1
+    } else {
+        0
+    };
+    CUMULATIVE_DAYS[month as usize - 1] + day + leap_day
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solar_position_equinox_noon() {
+        let time = SolarTime {
+            year: 2024,
+            month: 3,
+            day: 20,
+            hour: 12.0,
+            utc_offset: 0.0,
+        };
+        let (elevation, _) = solar_position(0.0, 0.0, &time);
+        assert!(elevation > 87.0);
+    }
+
+    #[test]
+    fn test_solar_position_morning_is_east() {
+        let time = SolarTime {
+            year: 2024,
+            month: 6,
+            day: 21,
+            hour: 8.0,
+            utc_offset: 2.0,
+        };
+        // Paris, early morning in summer: low sun in the east.
+        let (elevation, azimuth) = solar_position(48.85, 2.35, &time);
+        assert!(elevation > 10.0 && elevation < 40.0);
+        assert!(azimuth > 60.0 && azimuth < 120.0);
+    }
+
+    #[test]
+    fn test_solar_position_known_values() {
+        // Reference positions from the Astronomical Almanac's solar coordinates.
+        let cases = [
+            // Boulder, midsummer, noon daylight time: high in the south-east.
+            (
+                (40.015, -105.27),
+                (2024, 6, 21, 12.0, -6.0),
+                (68.75, 136.55),
+            ),
+            // London, winter solstice afternoon: low in the south-west.
+            ((51.5, -0.12), (2023, 12, 21, 14.0, 0.0), (10.46, 208.16)),
+            // Sydney, summer morning: east, seen from the southern hemisphere.
+            ((-33.87, 151.21), (2024, 1, 15, 9.0, 11.0), (35.22, 93.49)),
+        ];
+        for ((latitude, longitude), (year, month, day, hour, utc_offset), expected) in cases {
+            let time = SolarTime {
+                year,
+                month,
+                day,
+                hour,
+                utc_offset,
+            };
+            let (elevation, azimuth) = solar_position(latitude, longitude, &time);
+            assert!((elevation - expected.0).abs() < 0.5, "{elevation}");
+            assert!((azimuth - expected.1).abs() < 0.5, "{azimuth}");
+        }
+    }
+
+    #[test]
+    fn test_sky_finite_and_non_negative() {
+        for (elevation, turbidity) in [(90.0, 2.0), (45.0, 3.0), (5.0, 10.0), (0.0, 6.0)] {
+            let sky = PhysicalSky::new(elevation, 200.0).with_turbidity(turbidity);
+            for i in 0..=20 {
+                let theta = i as f64 / 20.0 * PI / 2.0;
+                for j in 0..40 {
+                    let phi = j as f64 / 40.0 * 2.0 * PI;
+                    let d = Vec3::new(
+                        theta.sin() * phi.cos(),
+                        theta.cos(),
+                        theta.sin() * phi.sin(),
+                    );
+                    let color = sky.value(d);
+                    for axis in 0..3 {
+                        assert!(color[axis].is_finite() && color[axis] >= 0.0, "{d:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_night_ground_is_black() {
+        let sky = PhysicalSky::new(-20.0, 90.0).with_ground_albedo(Vec3::zero());
+        assert_eq!(sky.value(sky.sun_direction()), Vec3::zero());
+        assert_eq!(sky.value(Vec3::new(0.3, -1.0, 0.2)), Vec3::zero());
+    }
+
+    #[test]
+    fn test_day_of_year() {
+        assert_eq!(day_of_year(2023, 3, 1), 60);
+        assert_eq!(day_of_year(2024, 3, 1), 61);
+    }
+
+    #[test]
+    fn test_sky_brighter_towards_sun() {
+        let sky = PhysicalSky::new(30.0, 90.0);
+        let sun = sky.sun_direction();
+        let near_sun = (sun + Vec3::new(0.0, 0.1, 0.0)).unit();
+        let away = Vec3::new(-sun.x, sun.y, -sun.z);
+        let luminance = |c: Vec3| c.x + c.y + c.z;
+        assert!(luminance(sky.value(near_sun)) > luminance(sky.value(away)));
+        assert!(luminance(sky.value(sun)) > 100.0 * luminance(sky.value(near_sun)));
+    }
+}
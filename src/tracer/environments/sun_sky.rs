@@ -0,0 +1,96 @@
+use crate::{degrees_to_radians, Environment, Vec3};
+
+/// A cheap procedural sky: a horizon-to-zenith blend, a glow around the sun,
+/// a bright sun disk and a flat ground color below the horizon.
+pub struct SunSky {
+    pub sun_direction: Vec3, // Direction towards the sun
+    pub sun_color: Vec3,     // Radiance of the sun disk
+    pub sun_radius: f64,     // Angular radius of the sun disk, in degrees
+    pub glow_exponent: f64,  // Sharpness of the halo around the sun
+    pub zenith_color: Vec3,  // Sky color straight up
+    pub horizon_color: Vec3, // Sky color at the horizon
+    pub ground_color: Vec3,  // Color below the horizon
+}
+
+impl SunSky {
+    pub fn new(sun_direction: Vec3) -> Self {
+        Self {
+            sun_direction: sun_direction.unit(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for SunSky {
+    fn default() -> Self {
+        Self {
+            sun_direction: Vec3::new(0.3, 0.6, -0.5).unit(),
+            sun_color: Vec3::new(50.0, 45.0, 38.0),
+            sun_radius: 0.53,
+            glow_exponent: 64.0,
+            zenith_color: Vec3::new(0.25, 0.45, 0.9),
+            horizon_color: Vec3::new(0.85, 0.9, 1.0),
+            ground_color: Vec3::new(0.3, 0.28, 0.25),
+        }
+    }
+}
+
+impl Environment for SunSky {
+    fn value(&self, direction: Vec3) -> Vec3 {
+        let d = direction.unit();
+        if d.y < 0.0 {
+            // Fade into the ground so the horizon has no hard seam.
+            let a = (-d.y * 10.0).min(1.0);
+            return self.horizon_color * (1.0 - a) + self.ground_color * a;
+        }
+
+        let a = d.y.sqrt();
+        let sky = self.horizon_color * (1.0 - a) + self.zenith_color * a;
+
+        let cos_sun = d.dot(self.sun_direction);
+        let glow = cos_sun.max(0.0).powf(self.glow_exponent) * 0.1 * self.sun_color;
+        if cos_sun >= degrees_to_radians(self.sun_radius).cos() {
+            return self.sun_color;
+        }
+        sky + glow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn test_sky_finite_and_non_negative() {
+        let sky = SunSky::default();
+        for i in 0..=20 {
+            let theta = i as f64 / 20.0 * PI / 2.0;
+            for j in 0..40 {
+                let phi = j as f64 / 40.0 * 2.0 * PI;
+                let d = Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+                let color = sky.value(d);
+                for axis in 0..3 {
+                    assert!(color[axis].is_finite() && color[axis] >= 0.0, "{d:?}");
+                }
+            }
+        }
+        assert_eq!(sky.value(sky.sun_direction), sky.sun_color);
+    }
+
+    #[test]
+    fn test_below_horizon_is_ground() {
+        let mut sky = SunSky::new(Vec3::new(0.2, -0.5, -1.0));
+        sky.ground_color = Vec3::zero();
+        // Neither the sun disk nor its glow shows through the ground.
+        assert_eq!(sky.value(sky.sun_direction), Vec3::zero());
+        assert_eq!(sky.value(Vec3::new(0.0, -1.0, 0.0)), Vec3::zero());
+        // Right at the horizon the ground fades into the sky.
+        assert!(sky.value(Vec3::new(1.0, -0.01, 0.0)).length() > 0.0);
+    }
+}
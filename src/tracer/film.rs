@@ -0,0 +1,35 @@
+use crate::{Aovs, Color};
+
+/// Light that integrators add to arbitrary pixels while rendering, such as paths traced
+/// from the lights that land somewhere else on the image than the camera ray's pixel.
+pub struct Film {
+    width: usize,
+    splats: Vec<Aovs>, // Only the lighting passes are used
+}
+
+impl Film {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            splats: vec![Aovs::default(); width * height],
+        }
+    }
+
+    /// Adds `color` to pixel (`i`, `j`), counted from the top left, after `bounces`
+    /// scattering events off non-specular surfaces. Like the camera's own estimates,
+    /// splats are divided by the number of samples per pixel.
+    pub fn splat(&mut self, i: usize, j: usize, color: Color, bounces: i32) {
+        self.splats[j * self.width + i].add_lighting(color, bounces, false);
+    }
+
+    /// Sum of everything splatted to pixel (`i`, `j`).
+    pub fn get(&self, i: usize, j: usize) -> Color {
+        let pixel = &self.splats[j * self.width + i];
+        pixel.emission + pixel.direct_diffuse + pixel.indirect_diffuse + pixel.specular
+    }
+
+    /// The splats to pixel (`i`, `j`) split into lighting passes.
+    pub fn lighting(&self, i: usize, j: usize) -> &Aovs {
+        &self.splats[j * self.width + i]
+    }
+}
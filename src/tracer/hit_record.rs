@@ -1,17 +1,22 @@
 use crate::Material;
 
-use super::{ray::Ray, vec3::Vec3};
+use super::{
+    ray::Ray,
+    vec3::{Normal3, Point3},
+};
 
 pub struct HitRecord<'a> {
-    pub normal: Vec3,
-    pub p: Vec3,
+    pub normal: Normal3,
+    pub p: Point3,
     pub t: f64,
+    pub u: f64,
+    pub v: f64,
     pub front_face: bool,
     pub material: &'a dyn Material,
 }
 
 impl<'a> HitRecord<'a> {
-    pub fn set_face_normal(&mut self, ray: &Ray, outward_normal: Vec3) {
+    pub fn set_face_normal(&mut self, ray: &Ray, outward_normal: Normal3) {
         // Sets the hit record normal vector.
         // NOTE: the parameter `outward_normal` is assumed to have unit length.
         self.front_face = ray.direction.dot(outward_normal) < 0.0;
@@ -1,18 +1,100 @@
-use crate::Interval;
+use std::sync::OnceLock;
+
+use crate::{random_f64, Aabb, Interval, Point3, Vec3};
 
 use super::{hit_record::HitRecord, ray::Ray};
 
 pub trait Hittable {
-    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord>;
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>>;
+
+    fn bounding_box(&self) -> Aabb;
+
+    /// Fraction of light that makes it along `ray` through this object within `ray_t`.
+    /// Surfaces block completely; participating media override this.
+    fn transmittance(&self, ray: &Ray, ray_t: Interval) -> f64 {
+        if self.hit(ray, ray_t).is_some() {
+            0.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Whether this object emits light and supports `pdf_value` and `random`, so it can
+    /// be sampled directly as a light source.
+    fn is_emissive(&self) -> bool {
+        false
+    }
+
+    /// Solid-angle density with which `random` picks `direction` from `origin`. Objects
+    /// that cannot be sampled as light sources return 0.
+    fn pdf_value(&self, _origin: Point3, _direction: Vec3) -> f64 {
+        0.0
+    }
+
+    /// A random direction from `origin` towards this object.
+    fn random(&self, _origin: Point3) -> Vec3 {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
+
+    /// Surface area of the points `sample_surface` picks from.
+    fn area(&self) -> f64 {
+        0.0
+    }
+
+    /// A point picked uniformly over the surface, for starting paths on a light. The
+    /// record holds the outward normal and `t` is zero. Objects that cannot be sampled
+    /// this way return `None`.
+    fn sample_surface(&self) -> Option<HitRecord<'_>> {
+        None
+    }
 }
 
 #[derive(Default)]
 pub struct HittableList {
     pub objects: Vec<Box<dyn Hittable>>,
+    /// Indices of the members `sampled_objects` yields and how many members there were
+    /// when they were found, on the first call. The list must not grow after that.
+    sampled: OnceLock<(usize, Vec<usize>)>,
+}
+
+impl HittableList {
+    /// Like `hit`, but also returns the index of the object that was hit.
+    pub fn hit_indexed(&self, ray: &Ray, ray_t: Interval) -> Option<(usize, HitRecord<'_>)> {
+        let mut closest_so_far = ray_t.max;
+        let mut res = None;
+        for (index, object) in self.objects.iter().enumerate() {
+            if let Some(tmp) = object.hit(ray, Interval::new(ray_t.min, closest_so_far)) {
+                closest_so_far = tmp.t;
+                res = Some((index, tmp));
+            }
+        }
+        res
+    }
+
+    /// The members `pdf_value`, `random` and `sample_surface` pick from: the emissive
+    /// ones if there are any, otherwise all of them, as for a list of light shapes built
+    /// just for sampling.
+    fn sampled_objects(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &dyn Hittable> + ExactSizeIterator + Clone {
+        let (len, indices) = self.sampled.get_or_init(|| {
+            let emissive: Vec<usize> = (0..self.objects.len())
+                .filter(|&index| self.objects[index].is_emissive())
+                .collect();
+            let indices = if emissive.is_empty() {
+                (0..self.objects.len()).collect()
+            } else {
+                emissive
+            };
+            (self.objects.len(), indices)
+        });
+        debug_assert_eq!(*len, self.objects.len(), "list grew after being sampled");
+        indices.iter().map(|&index| self.objects[index].as_ref())
+    }
 }
 
 impl Hittable for HittableList {
-    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
         let mut closest_so_far = ray_t.max;
         let mut res: Option<HitRecord> = None;
         for object in &self.objects {
@@ -23,4 +105,122 @@ impl Hittable for HittableList {
         }
         res
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.objects.iter().fold(Aabb::empty(), |bbox, object| {
+            Aabb::surrounding(&bbox, &object.bounding_box())
+        })
+    }
+
+    fn transmittance(&self, ray: &Ray, ray_t: Interval) -> f64 {
+        let mut transmittance = 1.0;
+        for object in &self.objects {
+            transmittance *= object.transmittance(ray, ray_t);
+            if transmittance == 0.0 {
+                break;
+            }
+        }
+        transmittance
+    }
+
+    fn is_emissive(&self) -> bool {
+        self.objects.iter().any(|object| object.is_emissive())
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let objects = self.sampled_objects();
+        if objects.len() == 0 {
+            return 0.0;
+        }
+        let weight = 1.0 / objects.len() as f64;
+        objects
+            .map(|object| weight * object.pdf_value(origin, direction))
+            .sum()
+    }
+
+    fn random(&self, origin: Point3) -> Vec3 {
+        let mut objects = self.sampled_objects();
+        if objects.len() == 0 {
+            return Vec3::new(1.0, 0.0, 0.0);
+        }
+        let index = ((random_f64() * objects.len() as f64) as usize).min(objects.len() - 1);
+        objects.nth(index).unwrap().random(origin)
+    }
+
+    fn area(&self) -> f64 {
+        self.sampled_objects().map(|object| object.area()).sum()
+    }
+
+    /// Picks a member with probability proportional to its area, so the point is
+    /// uniform over the whole list.
+    fn sample_surface(&self) -> Option<HitRecord<'_>> {
+        let objects = self.sampled_objects();
+        let total = objects.clone().map(|object| object.area()).sum::<f64>();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut target = random_f64() * total;
+        for object in objects.clone() {
+            let area = object.area();
+            if target < area {
+                return object.sample_surface();
+            }
+            target -= area;
+        }
+        objects
+            .rev()
+            .find(|object| object.area() > 0.0)?
+            .sample_surface()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DiffuseLight, Lambertian, Quad};
+
+    fn square(x: f64, side: f64) -> (Point3, Vec3, Vec3) {
+        (
+            Point3::new(x, 0.0, 0.0),
+            Vec3::new(0.0, side, 0.0),
+            Vec3::new(0.0, 0.0, side),
+        )
+    }
+
+    #[test]
+    fn test_samples_emissive_members_only() {
+        let mut list = HittableList::default();
+        let (q, u, v) = square(1.0, 3.0);
+        list.objects
+            .push(Box::new(Quad::new(q, u, v, Lambertian::new(Vec3::ones()))));
+        let (q, u, v) = square(2.0, 1.0);
+        list.objects.push(Box::new(Quad::new(
+            q,
+            u,
+            v,
+            DiffuseLight::new(Vec3::ones()),
+        )));
+
+        assert_eq!(list.area(), 1.0);
+        for _ in 0..100 {
+            assert_eq!(list.sample_surface().unwrap().p.x, 2.0);
+            assert_eq!(list.random(Point3::zero()).x, 2.0);
+        }
+    }
+
+    #[test]
+    fn test_samples_every_member_without_lights() {
+        let mut list = HittableList::default();
+        for x in [1.0, 2.0] {
+            let (q, u, v) = square(x, 1.0);
+            list.objects
+                .push(Box::new(Quad::new(q, u, v, Lambertian::new(Vec3::ones()))));
+        }
+        assert_eq!(list.area(), 2.0);
+        let n = 10_000;
+        let near = (0..n)
+            .filter(|_| list.random(Point3::zero()).x == 1.0)
+            .count();
+        assert!((near as f64 / n as f64 - 0.5).abs() < 0.03);
+    }
 }
@@ -0,0 +1,38 @@
+use crate::{Aovs, Camera, Color, Film, Hittable, Interval, Ray, Scene};
+
+/// A rendering strategy: turns camera rays into color estimates.
+pub trait Integrator {
+    /// Estimates the linear sRGB radiance arriving at the camera along `ray`.
+    fn li(&self, ray: &Ray, scene: &Scene) -> Color;
+
+    /// Like `li`, but with access to the camera, so that paths traced from the lights
+    /// can add light to whichever pixel they reach through `film`. Integrators that only
+    /// estimate the camera ray's own pixel leave this as `li`.
+    fn li_film(&self, ray: &Ray, scene: &Scene, _camera: &Camera, _film: &mut Film) -> Color {
+        self.li(ray, scene)
+    }
+
+    /// Like `li`, but also fills in the auxiliary passes for this sample. By default
+    /// only the first-hit passes are recorded and the lighting passes stay black;
+    /// integrators that can tell lighting apart override this.
+    fn li_aovs(&self, ray: &Ray, scene: &Scene, aovs: &mut Aovs) -> Color {
+        if let Some(hr) = scene.world.hit(ray, Interval::new(0.001, f64::INFINITY)) {
+            aovs.record_first_hit(ray, &hr);
+        }
+        self.li(ray, scene)
+    }
+
+    /// `li_film` and `li_aovs` together, for renders that write auxiliary passes. Splats
+    /// keep their lighting passes in `film`. Integrators that never splat leave this
+    /// as `li_aovs`.
+    fn li_film_aovs(
+        &self,
+        ray: &Ray,
+        scene: &Scene,
+        _camera: &Camera,
+        _film: &mut Film,
+        aovs: &mut Aovs,
+    ) -> Color {
+        self.li_aovs(ray, scene, aovs)
+    }
+}
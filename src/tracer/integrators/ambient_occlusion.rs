@@ -0,0 +1,91 @@
+use crate::{Color, CosinePdf, Hittable, Integrator, Interval, Pdf, Ray, Scene};
+
+/// Shades every surface by how much of its hemisphere is open within `max_distance`,
+/// ignoring lights and materials. Gives quick clay renders for checking geometry.
+pub struct AmbientOcclusionIntegrator {
+    pub max_distance: f64, // Occluders farther away than this are ignored
+    pub samples: i32,      // Occlusion rays per camera ray
+}
+
+impl AmbientOcclusionIntegrator {
+    pub fn new(max_distance: f64, samples: i32) -> Self {
+        Self {
+            max_distance,
+            samples,
+        }
+    }
+}
+
+impl Default for AmbientOcclusionIntegrator {
+    fn default() -> Self {
+        Self::new(f64::INFINITY, 16)
+    }
+}
+
+impl Integrator for AmbientOcclusionIntegrator {
+    fn li(&self, ray: &Ray, scene: &Scene) -> Color {
+        // Open sky is fully unoccluded.
+        let Some(hr) = scene.world.hit(ray, Interval::new(0.001, f64::INFINITY)) else {
+            return Color::ones();
+        };
+
+        // Cosine-weighted directions cancel the cosine in the occlusion integral, so the
+        // estimate is simply the fraction of rays that get away.
+        let pdf = CosinePdf::new(hr.normal);
+        let samples = self.samples.max(1);
+        let unoccluded = (0..samples)
+            .filter(|_| {
+                let direction = pdf.generate().unit();
+                let occlusion_ray = Ray::with_time(hr.p, direction, ray.time);
+                scene
+                    .world
+                    .hit(&occlusion_ray, Interval::new(0.001, self.max_distance))
+                    .is_none()
+            })
+            .count();
+
+        Color::ones() * (unoccluded as f64 / samples as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GradientSky, HittableList, Lambertian, Plane, Sphere, Vec3};
+
+    fn scene_of(object: Box<dyn Hittable>) -> Scene {
+        let mut world = HittableList::default();
+        world.objects.push(object);
+        Scene::new(world, Box::new(GradientSky::default()))
+    }
+
+    #[test]
+    fn test_isolated_plane_is_unoccluded() {
+        let scene = scene_of(Box::new(Plane::new(
+            Vec3::zero(),
+            Vec3::new(0.0, 1.0, 0.0),
+            Lambertian::new(Color::ones()),
+        )));
+        let integrator = AmbientOcclusionIntegrator::new(f64::INFINITY, 64);
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.2, -1.0, 0.1));
+        assert_eq!(integrator.li(&ray, &scene), Color::ones());
+    }
+
+    #[test]
+    fn test_inside_closed_sphere_is_occluded() {
+        let scene = scene_of(Box::new(Sphere::new(
+            Vec3::zero(),
+            1.0,
+            Lambertian::new(Color::ones()),
+        )));
+        let integrator = AmbientOcclusionIntegrator::new(3.0, 64);
+        let ray = Ray::new(Vec3::zero(), Vec3::new(0.3, 0.4, -1.0));
+        assert_eq!(integrator.li(&ray, &scene), Color::zero());
+
+        // With a short reach, only rays that graze the wall find it in range. A chord at
+        // angle theta from the normal is 2 cos(theta) long, so 1/16 of them are blocked.
+        let integrator = AmbientOcclusionIntegrator::new(0.5, 1024);
+        let open = integrator.li(&ray, &scene).x;
+        assert!((open - 15.0 / 16.0).abs() < 0.05, "{open}");
+    }
+}
@@ -0,0 +1,801 @@
+use std::f64::consts::PI;
+
+use crate::{
+    random_cosine_direction, random_f64, Aovs, Camera, Color, Film, HitRecord, Hittable,
+    Integrator, Interval, Onb, Point3, Ray, Scene, Vec3,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum VertexKind {
+    Camera,
+    Light,
+    Surface,
+}
+
+/// One vertex of a camera or light subpath.
+struct Vertex<'a> {
+    kind: VertexKind,
+    p: Point3,
+    normal: Vec3,          // Surface normal; unused at the camera
+    object: Option<usize>, // Index of the top-level world object the vertex lies on
+    hit: Option<HitRecord<'a>>,
+    incoming: Ray, // Ray the subpath arrived along; the primary ray at endpoints
+    beta: Color,   // Subpath throughput up to this vertex
+    delta: bool,   // Whether the vertex scattered specularly
+    pdf_fwd: f64,  // Area density of sampling this vertex from its predecessor
+    pdf_rev: f64,  // Area density of sampling it from its successor instead
+}
+
+impl<'a> Vertex<'a> {
+    fn camera(ray: &Ray) -> Self {
+        Self {
+            kind: VertexKind::Camera,
+            p: ray.origin,
+            normal: Vec3::zero(),
+            hit: None,
+            incoming: *ray,
+            object: None,
+            beta: Color::ones(),
+            delta: false,
+            pdf_fwd: 0.0,
+            pdf_rev: 0.0,
+        }
+    }
+
+    /// BSDF times cosine for light scattered at this vertex towards `next`.
+    fn f(&self, next: Point3) -> Color {
+        match (&self.hit, self.kind) {
+            (Some(hr), VertexKind::Surface) => hr.material.eval(&self.incoming, hr, next - self.p),
+            _ => Color::zero(),
+        }
+    }
+
+    /// Turns a solid-angle density at this vertex into an area density at `next`.
+    fn convert_density(&self, pdf: f64, next: &Vertex) -> f64 {
+        let w = next.p - self.p;
+        let distance_squared = w.length_squard();
+        if distance_squared == 0.0 {
+            return 0.0;
+        }
+        let mut pdf = pdf / distance_squared;
+        if next.kind != VertexKind::Camera {
+            pdf *= next.normal.dot(w).abs() / distance_squared.sqrt();
+        }
+        pdf
+    }
+
+    /// Area density with which this vertex samples `next`, having been reached from
+    /// `prev`.
+    fn pdf(&self, camera: Option<&Camera>, prev: Option<&Vertex>, next: &Vertex) -> f64 {
+        let direction = next.p - self.p;
+        let pdf = match self.kind {
+            VertexKind::Camera => camera.map_or(0.0, |c| c.direction_pdf(self.p, direction)),
+            VertexKind::Light => return self.pdf_light(next),
+            VertexKind::Surface => {
+                let (Some(hr), Some(prev)) = (&self.hit, prev) else {
+                    return 0.0;
+                };
+                let incoming = Ray::with_time(prev.p, self.p - prev.p, self.incoming.time);
+                hr.material.pdf(&incoming, hr, direction)
+            }
+        };
+        self.convert_density(pdf, next)
+    }
+
+    /// Area density with which a light subpath starting here goes on to `next`.
+    fn pdf_light(&self, next: &Vertex) -> f64 {
+        let cosine = self.normal.dot((next.p - self.p).unit()).abs();
+        self.convert_density(cosine / (2.0 * PI), next)
+    }
+
+    /// Area density with which a light subpath starts at this point.
+    fn pdf_light_origin(&self, scene: &Scene) -> f64 {
+        match self.object {
+            Some(index) if scene.lights.contains(&index) => {
+                let area = scene.world.objects[index].area();
+                if area > 0.0 {
+                    1.0 / (scene.lights.len() as f64 * area)
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// Bidirectional path tracer. Each sample traces a subpath from the camera and one from
+/// a light, and connects every pair of their vertices, including connecting light
+/// vertices straight to the camera. Multiple importance sampling with the balance
+/// heuristic weighs the strategies against each other, so caustics and scenes lit
+/// through small openings converge far faster than with `PathIntegrator`.
+///
+/// Light subpaths start on area lights. Delta lights are only sampled from camera
+/// vertices and the environment is only found by camera rays escaping. Overlapping
+/// dielectrics and dispersion are not taken into account, and participating media
+/// are treated as surfaces.
+///
+/// Connecting to the camera needs the camera itself, so only `Camera::render` gets
+/// every strategy. Plain `li` leaves that one out, which is still unbiased.
+pub struct BidirectionalIntegrator {
+    pub max_depth: i32, // Maximum number of bounces of a full path
+}
+
+impl BidirectionalIntegrator {
+    pub fn new(max_depth: i32) -> Self {
+        Self { max_depth }
+    }
+
+    /// Estimates the light along `ray`, splitting it into lighting passes in `aovs`.
+    /// Connections straight to the camera go to `film` instead, when both are given.
+    fn trace(
+        &self,
+        ray: &Ray,
+        scene: &Scene,
+        camera: Option<&Camera>,
+        mut film: Option<&mut Film>,
+        aovs: &mut Aovs,
+    ) -> Color {
+        let max_depth = self.max_depth.max(0) as usize;
+
+        let mut camera_path = vec![Vertex::camera(ray)];
+        let pdf_dir = camera.map_or(0.0, |c| c.direction_pdf(ray.origin, ray.direction));
+        let environment = self.random_walk(
+            scene,
+            *ray,
+            Color::ones(),
+            pdf_dir,
+            max_depth + 1,
+            &mut camera_path,
+        );
+        let light_path = self.light_subpath(scene, ray.time, max_depth);
+
+        let specular = camera_path.get(1).is_some_and(|vertex| vertex.delta);
+        if let Some(hr) = camera_path.get(1).and_then(|vertex| vertex.hit.as_ref()) {
+            aovs.record_first_hit(ray, hr);
+        }
+        let mut radiance = Color::zero();
+        let mut add = |contribution: Color, bounces: usize| {
+            radiance += contribution;
+            aovs.add_lighting(contribution, bounces as i32, specular);
+        };
+        // Only a camera ray that escaped at the end of its walk finds the environment.
+        add(environment, camera_path.len() - 1);
+
+        // Delta lights can only be reached by shadow rays from the camera subpath.
+        for (bounces, vertex) in camera_path.iter().enumerate().take(max_depth + 1).skip(1) {
+            if let Some(hr) = &vertex.hit {
+                add(
+                    vertex.beta * scene.delta_lighting(&vertex.incoming, hr),
+                    bounces,
+                );
+            }
+        }
+
+        for t in 1..=camera_path.len() {
+            for s in 0..=light_path.len() {
+                if (s == 1 && t == 1) || s + t < 2 || s + t - 2 > max_depth {
+                    continue;
+                }
+                if t == 1 && camera.is_none() {
+                    continue;
+                }
+
+                let (contribution, pixel) =
+                    self.connect(scene, camera, &light_path, &camera_path, s, t);
+                let bounces = s + t - 2;
+                if t > 1 {
+                    add(contribution, bounces);
+                } else if let (Some(film), Some((i, j))) = (film.as_deref_mut(), pixel) {
+                    film.splat(i, j, contribution, bounces as i32);
+                }
+            }
+        }
+
+        radiance
+    }
+
+    /// Extends `path` by following `ray` and sampling the materials it meets, with at
+    /// most `max_vertices` new vertices. `pdf_dir` is the solid-angle density `ray` was
+    /// sampled with. Returns the light from the environment found by escaping rays.
+    fn random_walk<'a>(
+        &self,
+        scene: &'a Scene,
+        mut ray: Ray,
+        mut beta: Color,
+        mut pdf_dir: f64,
+        max_vertices: usize,
+        path: &mut Vec<Vertex<'a>>,
+    ) -> Color {
+        let mut environment = Color::zero();
+        for _ in 0..max_vertices {
+            let Some((object, hr)) = scene
+                .world
+                .hit_indexed(&ray, Interval::new(0.001, f64::INFINITY))
+            else {
+                if path[0].kind == VertexKind::Camera {
+                    environment += beta * scene.environment.value(ray.direction);
+                }
+                break;
+            };
+            let scattered = hr.material.scatter(&ray, &hr);
+
+            let mut vertex = Vertex {
+                kind: VertexKind::Surface,
+                p: hr.p,
+                normal: hr.normal,
+                hit: None,
+                incoming: ray,
+                object: Some(object),
+                beta,
+                delta: false,
+                pdf_fwd: 0.0,
+                pdf_rev: 0.0,
+            };
+            let prev = path.last_mut().expect("subpaths start with an endpoint");
+            vertex.pdf_fwd = prev.convert_density(pdf_dir, &vertex);
+
+            let Some(sr) = scattered else {
+                vertex.hit = Some(hr);
+                path.push(vertex);
+                break;
+            };
+
+            // Density of sampling the way back, for weighing strategies that build this
+            // part of the path from the other end.
+            let pdf_rev = if sr.is_specular {
+                vertex.delta = true;
+                pdf_dir = 0.0;
+                0.0
+            } else {
+                pdf_dir = sr.pdf;
+                let reverse = Ray::with_time(hr.p, -sr.scatter_ray.direction, ray.time);
+                hr.material.pdf(&reverse, &hr, -ray.direction)
+            };
+            prev.pdf_rev = vertex.convert_density(pdf_rev, prev);
+
+            beta = beta * sr.attenuation;
+            vertex.hit = Some(hr);
+            path.push(vertex);
+            if beta.near_zero() {
+                break;
+            }
+            ray = sr.scatter_ray;
+        }
+        environment
+    }
+
+    /// Picks a light and a point on it uniformly by area.
+    fn sample_light<'a>(&self, scene: &'a Scene, time: f64) -> Option<Vertex<'a>> {
+        if scene.lights.is_empty() {
+            return None;
+        }
+        let choice =
+            ((random_f64() * scene.lights.len() as f64) as usize).min(scene.lights.len() - 1);
+        let index = scene.lights[choice];
+        let hr = scene.world.objects[index].sample_surface()?;
+
+        let mut vertex = Vertex {
+            kind: VertexKind::Light,
+            p: hr.p,
+            normal: hr.normal,
+            hit: None,
+            incoming: Ray::with_time(hr.p, hr.normal, time),
+            object: Some(index),
+            beta: Color::zero(),
+            delta: false,
+            pdf_fwd: 0.0,
+            pdf_rev: 0.0,
+        };
+        vertex.hit = Some(hr);
+        vertex.pdf_fwd = vertex.pdf_light_origin(scene);
+        (vertex.pdf_fwd > 0.0).then_some(vertex)
+    }
+
+    fn light_subpath<'a>(&self, scene: &'a Scene, time: f64, max_depth: usize) -> Vec<Vertex<'a>> {
+        let Some(mut light) = self.sample_light(scene, time) else {
+            return Vec::new();
+        };
+
+        // Lights emit from both sides, so leave a random side with a cosine distribution.
+        let normal = if random_f64() < 0.5 {
+            light.normal
+        } else {
+            -light.normal
+        };
+        let direction = Onb::new(normal).transform(random_cosine_direction());
+        let cosine = normal.dot(direction);
+        let pdf_dir = cosine / (2.0 * PI);
+        if pdf_dir <= 0.0 {
+            return Vec::new();
+        }
+
+        let hr = light.hit.as_ref().expect("lights carry their surface");
+        let emitted = hr
+            .material
+            .emitted(&Ray::with_time(light.p + direction, -direction, time), hr);
+        light.beta = emitted;
+        let beta = emitted * cosine / (light.pdf_fwd * pdf_dir);
+
+        let mut path = vec![light];
+        let ray = Ray::with_time(path[0].p, direction, time);
+        self.random_walk(scene, ray, beta, pdf_dir, max_depth, &mut path);
+        path
+    }
+
+    /// Light carried by the path made of the first `s` light vertices and the first `t`
+    /// camera vertices, weighted against the other ways of building it. Connections to
+    /// the camera also return the pixel they land in.
+    fn connect(
+        &self,
+        scene: &Scene,
+        camera: Option<&Camera>,
+        light_path: &[Vertex],
+        camera_path: &[Vertex],
+        s: usize,
+        t: usize,
+    ) -> (Color, Option<(usize, usize)>) {
+        let none = (Color::zero(), None);
+        let time = camera_path[0].incoming.time;
+        let mut pixel = None;
+        let mut sampled = None;
+
+        let contribution = if s == 0 {
+            // The camera subpath found a light by itself.
+            let pt = &camera_path[t - 1];
+            let Some(hr) = &pt.hit else {
+                return none;
+            };
+            pt.beta * hr.material.emitted(&pt.incoming, hr)
+        } else if t == 1 {
+            // Connect a light subpath vertex straight to the camera.
+            let qs = &light_path[s - 1];
+            let Some(camera) = camera else {
+                return none;
+            };
+            if qs.delta {
+                return none;
+            }
+            let Some((lens, raster, weight)) = camera.sample_importance(qs.p) else {
+                return none;
+            };
+            sampled = Some(Vertex::camera(&Ray::with_time(lens, qs.p - lens, time)));
+            pixel = Some(raster);
+
+            let contribution = qs.beta * qs.f(lens) * weight;
+            if contribution.near_zero() {
+                return none;
+            }
+            contribution * visibility(scene, qs.p, lens, time)
+        } else if s == 1 {
+            // Connect a camera subpath vertex to a freshly sampled point on a light.
+            let pt = &camera_path[t - 1];
+            if pt.delta {
+                return none;
+            }
+            let Some(light) = self.sample_light(scene, time) else {
+                return none;
+            };
+            let w = light.p - pt.p;
+            let distance_squared = w.length_squard();
+            let cosine = light.normal.dot(w.unit()).abs();
+            let hr = light.hit.as_ref().expect("lights carry their surface");
+            let emitted = hr.material.emitted(&Ray::with_time(pt.p, w, time), hr);
+
+            let contribution =
+                pt.beta * pt.f(light.p) * emitted * cosine / (distance_squared * light.pdf_fwd);
+            let light_p = light.p;
+            sampled = Some(light);
+            if contribution.near_zero() {
+                return none;
+            }
+            contribution * visibility(scene, pt.p, light_p, time)
+        } else {
+            // Join the two subpaths in the middle.
+            let qs = &light_path[s - 1];
+            let pt = &camera_path[t - 1];
+            if qs.delta || pt.delta {
+                return none;
+            }
+            let distance_squared = (qs.p - pt.p).length_squard();
+            let contribution = qs.beta * qs.f(pt.p) * pt.f(qs.p) * pt.beta / distance_squared;
+            if contribution.near_zero() {
+                return none;
+            }
+            contribution * visibility(scene, qs.p, pt.p, time)
+        };
+
+        if contribution.near_zero() {
+            return none;
+        }
+        let subpaths = Subpaths {
+            scene,
+            camera,
+            light_path,
+            camera_path,
+            sampled: sampled.as_ref(),
+        };
+        (subpaths.mis_weight(s, t) * contribution, pixel)
+    }
+}
+
+/// Everything needed to weigh one strategy against the others: both subpaths, plus the
+/// endpoint sampled fresh for the connection, if any, which stands in for the first
+/// vertex of its subpath.
+struct Subpaths<'a> {
+    scene: &'a Scene,
+    camera: Option<&'a Camera>,
+    light_path: &'a [Vertex<'a>],
+    camera_path: &'a [Vertex<'a>],
+    sampled: Option<&'a Vertex<'a>>,
+}
+
+impl Subpaths<'_> {
+    /// Balance heuristic weight of the strategy with `s` light and `t` camera vertices,
+    /// found by walking along the path and asking how likely every other split would
+    /// have been to produce it.
+    fn mis_weight(&self, s: usize, t: usize) -> f64 {
+        let Self {
+            scene,
+            camera,
+            light_path,
+            camera_path,
+            sampled,
+        } = *self;
+        if s + t == 2 {
+            return 1.0;
+        }
+
+        // The vertices of this path, with a freshly sampled endpoint swapped in.
+        let light_vertex = |i: usize| match sampled {
+            Some(vertex) if s == 1 && i == 0 => vertex,
+            _ => &light_path[i],
+        };
+        let camera_vertex = |i: usize| match sampled {
+            Some(vertex) if t == 1 && i == 0 => vertex,
+            _ => &camera_path[i],
+        };
+        let densities = |v: &Vertex| (v.pdf_fwd, v.pdf_rev, v.delta);
+        let mut light: Vec<_> = (0..s).map(|i| densities(light_vertex(i))).collect();
+        let mut cam: Vec<_> = (0..t).map(|i| densities(camera_vertex(i))).collect();
+
+        let pt = camera_vertex(t - 1);
+        let pt_minus = (t > 1).then(|| camera_vertex(t - 2));
+        let qs = (s > 0).then(|| light_vertex(s - 1));
+        let qs_minus = (s > 1).then(|| light_vertex(s - 2));
+
+        // Densities across the new connection, in both directions.
+        cam[t - 1].1 = match qs {
+            Some(qs) => qs.pdf(camera, qs_minus, pt),
+            None => pt.pdf_light_origin(scene),
+        };
+        if s == 0 && cam[t - 1].1 == 0.0 {
+            // A light that light subpaths never start from.
+            return 1.0;
+        }
+        if let Some(pt_minus) = pt_minus {
+            cam[t - 2].1 = match qs {
+                Some(qs) => pt.pdf(camera, Some(qs), pt_minus),
+                None => pt.pdf_light(pt_minus),
+            };
+        }
+        if let Some(qs) = qs {
+            light[s - 1].1 = pt.pdf(camera, pt_minus, qs);
+            light[s - 1].2 = false;
+        }
+        if let (Some(qs), Some(qs_minus)) = (qs, qs_minus) {
+            light[s - 2].1 = qs.pdf(camera, Some(pt), qs_minus);
+        }
+        cam[t - 1].2 = false;
+
+        // Specular vertices have zero densities; they cancel out of the ratios.
+        let remap = |pdf: f64| if pdf != 0.0 { pdf } else { 1.0 };
+        let mut sum = 0.0;
+
+        let mut ratio = 1.0;
+        for i in (1..t).rev() {
+            ratio *= remap(cam[i].1) / remap(cam[i].0);
+            // The split with a single camera vertex needs the camera to connect to.
+            if !cam[i].2 && !cam[i - 1].2 && (i > 1 || camera.is_some()) {
+                sum += ratio;
+            }
+        }
+
+        let mut ratio = 1.0;
+        for i in (0..s).rev() {
+            ratio *= remap(light[i].1) / remap(light[i].0);
+            let delta_before = i > 0 && light[i - 1].2;
+            if !light[i].2 && !delta_before {
+                sum += ratio;
+            }
+        }
+
+        1.0 / (1.0 + sum)
+    }
+}
+
+impl Default for BidirectionalIntegrator {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl Integrator for BidirectionalIntegrator {
+    fn li(&self, ray: &Ray, scene: &Scene) -> Color {
+        self.trace(ray, scene, None, None, &mut Aovs::default())
+    }
+
+    fn li_film(&self, ray: &Ray, scene: &Scene, camera: &Camera, film: &mut Film) -> Color {
+        self.trace(ray, scene, Some(camera), Some(film), &mut Aovs::default())
+    }
+
+    fn li_aovs(&self, ray: &Ray, scene: &Scene, aovs: &mut Aovs) -> Color {
+        self.trace(ray, scene, None, None, aovs)
+    }
+
+    fn li_film_aovs(
+        &self,
+        ray: &Ray,
+        scene: &Scene,
+        camera: &Camera,
+        film: &mut Film,
+        aovs: &mut Aovs,
+    ) -> Color {
+        self.trace(ray, scene, Some(camera), Some(film), aovs)
+    }
+}
+
+/// Fraction of light getting from `from` to `to` unobstructed.
+fn visibility(scene: &Scene, from: Point3, to: Point3, time: f64) -> f64 {
+    let direction = to - from;
+    let distance = direction.length();
+    let ray = Ray::with_time(from, direction / distance, time);
+    scene
+        .world
+        .transmittance(&ray, Interval::new(0.001, distance - 0.001))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::{
+        ConstantEnvironment, DiffuseLight, HittableList, Lambertian, Mat4, PathIntegrator, Quad,
+        Transform,
+    };
+
+    fn lambertian() -> Lambertian {
+        Lambertian::new(Color::new(0.6, 0.6, 0.6))
+    }
+
+    fn camera(look_from: Point3, look_at: Point3, image_width: f64) -> Camera {
+        let mut camera = Camera::default();
+        camera.image_width = image_width;
+        camera.look_from = look_from;
+        camera.look_at = look_at;
+        camera.initialize();
+        camera
+    }
+
+    /// The vertex where a ray from `from` towards `to` first hits the world.
+    fn vertex<'a>(scene: &'a Scene, kind: VertexKind, from: Point3, to: Point3) -> Vertex<'a> {
+        let ray = Ray::new(from, to - from);
+        let (object, hr) = scene
+            .world
+            .hit_indexed(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        Vertex {
+            kind,
+            p: hr.p,
+            normal: hr.normal,
+            object: Some(object),
+            hit: Some(hr),
+            incoming: ray,
+            beta: Color::ones(),
+            delta: false,
+            pdf_fwd: 0.0,
+            pdf_rev: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_mis_weights_sum_to_one() {
+        // A floor, a back wall and a light above them.
+        let mut world = HittableList::default();
+        world.objects.push(Box::new(Quad::new(
+            Vec3::new(-2.0, 0.0, -2.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 4.0),
+            lambertian(),
+        )));
+        world.objects.push(Box::new(Quad::new(
+            Vec3::new(-2.0, 0.0, -2.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 4.0, 0.0),
+            lambertian(),
+        )));
+        world.objects.push(Box::new(Quad::new(
+            Vec3::new(-1.0, 3.0, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            DiffuseLight::new(Color::ones()),
+        )));
+        let scene = Scene::new(world, Box::new(ConstantEnvironment::new(Color::zero())));
+        let eye = Vec3::new(0.0, 1.0, 3.0);
+        let camera = camera(eye, Vec3::zero(), 20.0);
+
+        // The path eye -> floor -> wall -> light, seen from either end.
+        let points = [
+            eye,
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.5, 1.5, -2.0),
+            Vec3::new(0.2, 3.0, 0.3),
+        ];
+        let n = points.len();
+        let build = |k: usize, kind: VertexKind| match k {
+            0 => Vertex::camera(&Ray::new(eye, points[1] - eye)),
+            _ => vertex(&scene, kind, points[k - 1], points[k]),
+        };
+        let surface = |k: usize| build(k, VertexKind::Surface);
+
+        // Area densities of each vertex when walking from the camera and from the light.
+        let camera = Some(&camera);
+        let from_camera: Vec<_> = (0..n)
+            .map(|k| match k {
+                0 => 0.0,
+                1 => surface(0).pdf(camera, None, &surface(1)),
+                _ => surface(k - 1).pdf(camera, Some(&surface(k - 2)), &surface(k)),
+            })
+            .collect();
+        let light = build(n - 1, VertexKind::Light);
+        let from_light: Vec<_> = (0..n)
+            .map(|k| match n - 1 - k {
+                0 => light.pdf_light_origin(&scene),
+                1 => light.pdf_light(&surface(k)),
+                2 => surface(k + 1).pdf(camera, Some(&light), &surface(k)),
+                _ => surface(k + 1).pdf(camera, Some(&surface(k + 2)), &surface(k)),
+            })
+            .collect();
+        assert!(from_camera[1..]
+            .iter()
+            .chain(&from_light)
+            .all(|&pdf| pdf > 0.0));
+
+        let mut total = 0.0;
+        for s in 0..n {
+            let t = n - s;
+            let camera_path: Vec<_> = (0..t)
+                .map(|k| Vertex {
+                    pdf_fwd: from_camera[k],
+                    pdf_rev: from_light[k],
+                    ..surface(k)
+                })
+                .collect();
+            let light_path: Vec<_> = (0..s)
+                .map(|i| {
+                    let k = n - 1 - i;
+                    let kind = if i == 0 {
+                        VertexKind::Light
+                    } else {
+                        VertexKind::Surface
+                    };
+                    Vertex {
+                        pdf_fwd: from_light[k],
+                        pdf_rev: from_camera[k],
+                        ..build(k, kind)
+                    }
+                })
+                .collect();
+            let subpaths = Subpaths {
+                scene: &scene,
+                camera,
+                light_path: &light_path,
+                camera_path: &camera_path,
+                sampled: None,
+            };
+            let weight = subpaths.mis_weight(s, t);
+            assert!(weight > 0.0 && weight < 1.0, "s = {s}: {weight}");
+            total += weight;
+        }
+        assert!((total - 1.0).abs() < 1e-9, "{total}");
+    }
+
+    /// Renders a closed box of diffuse walls under `ceiling`, a light covering the top
+    /// face, with both integrators and compares the mean brightness.
+    fn assert_matches_path_tracer(ceiling: Box<dyn Hittable>) {
+        let (a, b) = (Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
+        let (dx, dy, dz) = (
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Vec3::new(0.0, 0.0, 2.0),
+        );
+        let mut world = HittableList::default();
+        world
+            .objects
+            .push(Box::new(Quad::new(a, dx, dz, lambertian())));
+        world
+            .objects
+            .push(Box::new(Quad::new(a, dx, dy, lambertian())));
+        world
+            .objects
+            .push(Box::new(Quad::new(a, dy, dz, lambertian())));
+        world
+            .objects
+            .push(Box::new(Quad::new(b, -dx, -dy, lambertian())));
+        world
+            .objects
+            .push(Box::new(Quad::new(b, -dy, -dz, lambertian())));
+        world.objects.push(ceiling);
+        let scene = Scene::new(world, Box::new(ConstantEnvironment::new(Color::zero())));
+        let camera = camera(Vec3::new(0.0, 0.0, 0.9), Vec3::zero(), 8.0);
+        let (width, height) = (8, 8);
+        let samples_per_pixel = 64;
+
+        let bidirectional = BidirectionalIntegrator::new(12);
+        let path = PathIntegrator::new(16);
+        let mut film = Film::new(width, height);
+        let (mut bdpt_sum, mut path_sum) = (Color::zero(), Color::zero());
+        for j in 0..height {
+            for i in 0..width {
+                for _ in 0..samples_per_pixel {
+                    let ray = camera.get_ray(i as i32, j as i32);
+                    let mut aovs = Aovs::default();
+                    let color =
+                        bidirectional.li_film_aovs(&ray, &scene, &camera, &mut film, &mut aovs);
+                    let passes =
+                        aovs.emission + aovs.direct_diffuse + aovs.indirect_diffuse + aovs.specular;
+                    assert!((passes - color).length() <= 1e-9 * color.length().max(1.0));
+                    bdpt_sum += color;
+
+                    let ray = camera.get_ray(i as i32, j as i32);
+                    path_sum += path.li(&ray, &scene);
+                }
+            }
+        }
+        // Light traced straight to the camera lands on the film.
+        let splats = (0..width * height).fold(Color::zero(), |sum, index| {
+            sum + film.get(index % width, index / width)
+        });
+        assert!(splats.x > 0.0);
+        bdpt_sum += splats;
+
+        let bdpt_mean = bdpt_sum.x / (width * height * samples_per_pixel) as f64;
+        let path_mean = path_sum.x / (width * height * samples_per_pixel) as f64;
+        assert!(
+            (bdpt_mean - path_mean).abs() < 0.05 * path_mean,
+            "{bdpt_mean} vs {path_mean}"
+        );
+    }
+
+    fn glow() -> DiffuseLight {
+        DiffuseLight::new(Color::new(2.0, 2.0, 2.0))
+    }
+
+    #[test]
+    fn test_matches_path_tracer() {
+        assert_matches_path_tracer(Box::new(Quad::new(
+            Vec3::new(1.0, 1.0, 1.0),
+            Vec3::new(-2.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -2.0),
+            glow(),
+        )));
+    }
+
+    #[test]
+    fn test_matches_path_tracer_with_transformed_light() {
+        // A unit square in the z = 0 plane, turned, doubled and lifted onto the ceiling.
+        let square: Arc<dyn Hittable> = Arc::new(Quad::new(
+            Vec3::new(-0.5, -0.5, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            glow(),
+        ));
+        let matrix = Mat4::translation(Vec3::new(0.0, 1.0, 0.0))
+            * Mat4::scaling(Vec3::new(2.0, 2.0, 2.0))
+            * Mat4::rotation(Vec3::new(1.0, 0.0, 0.0), 90.0);
+        let light = Transform::new(square, matrix);
+        assert!((light.area() - 4.0).abs() < 1e-9);
+        assert_matches_path_tracer(Box::new(light));
+    }
+}
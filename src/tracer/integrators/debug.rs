@@ -0,0 +1,118 @@
+use crate::{Color, HitRecord, Integrator, Interval, Ray, Scene, Vec3};
+
+/// A property of the first hit to visualize.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DebugChannel {
+    /// The normal used for shading, which always faces the incoming ray.
+    ShadingNormal,
+    /// The outward-facing surface normal.
+    GeometricNormal,
+    /// Distance along the ray, from black at the camera to white at `max`.
+    Distance { max: f64 },
+    /// The material's albedo.
+    Albedo,
+    /// Texture coordinates as red and green.
+    Uv,
+    /// The surface coordinates read as barycentric weights (1 - u - v, u, v). There are
+    /// no triangle primitives yet, so this shows each shape's (u, v) parameterization.
+    Barycentric,
+    /// A distinct color per material.
+    MaterialId,
+    /// A distinct color per top-level object in the world.
+    ObjectId,
+}
+
+/// Renders one property of the first hit as a false-color image, without lighting.
+/// Rays that miss everything are black.
+pub struct DebugIntegrator {
+    pub channel: DebugChannel,
+}
+
+impl DebugIntegrator {
+    pub fn new(channel: DebugChannel) -> Self {
+        Self { channel }
+    }
+
+    fn shade(&self, ray: &Ray, index: usize, hr: &HitRecord) -> Color {
+        let normal_color = |n: Vec3| (n.unit() + Vec3::ones()) / 2.0;
+        match self.channel {
+            DebugChannel::ShadingNormal => normal_color(hr.normal),
+            DebugChannel::GeometricNormal => {
+                normal_color(if hr.front_face { hr.normal } else { -hr.normal })
+            }
+            DebugChannel::Distance { max } => {
+                let distance = hr.t * ray.direction.length();
+                Color::ones() * (distance / max).clamp(0.0, 1.0)
+            }
+            DebugChannel::Albedo => hr.material.albedo(hr),
+            DebugChannel::Uv => Color::new(hr.u, hr.v, 0.0),
+            DebugChannel::Barycentric => Color::new((1.0 - hr.u - hr.v).max(0.0), hr.u, hr.v),
+            DebugChannel::MaterialId => id_color(hr.material.material_id() as u64),
+            DebugChannel::ObjectId => id_color(index as u64),
+        }
+    }
+}
+
+impl Integrator for DebugIntegrator {
+    fn li(&self, ray: &Ray, scene: &Scene) -> Color {
+        match scene
+            .world
+            .hit_indexed(ray, Interval::new(0.001, f64::INFINITY))
+        {
+            Some((index, hr)) => self.shade(ray, index, &hr),
+            None => Color::zero(),
+        }
+    }
+}
+
+/// Hashes an identifier to a bright, well-spread color.
+fn id_color(id: u64) -> Color {
+    // SplitMix64 finalizer.
+    let mut x = id.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^= x >> 31;
+
+    let channel = |shift: u32| 0.2 + 0.8 * ((x >> shift) & 0xff) as f64 / 255.0;
+    Color::new(channel(0), channel(8), channel(16))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{GradientSky, HittableList, Lambertian, Sphere};
+
+    #[test]
+    fn test_shared_material_has_one_color() {
+        let shared = Arc::new(Lambertian::new(Color::new(0.8, 0.2, 0.2)));
+        let mut world = HittableList::default();
+        world.objects.push(Box::new(Sphere::new(
+            Vec3::new(-2.0, 0.0, 0.0),
+            1.0,
+            shared.clone(),
+        )));
+        world
+            .objects
+            .push(Box::new(Sphere::new(Vec3::new(2.0, 0.0, 0.0), 1.0, shared)));
+        // Looks the same, but is a different material.
+        world.objects.push(Box::new(Sphere::new(
+            Vec3::new(0.0, 3.0, 0.0),
+            1.0,
+            Arc::new(Lambertian::new(Color::new(0.8, 0.2, 0.2))),
+        )));
+        let scene = Scene::new(world, Box::new(GradientSky::default()));
+
+        let integrator = DebugIntegrator::new(DebugChannel::MaterialId);
+        let color_at = |x: f64, y: f64| {
+            integrator.li(
+                &Ray::new(Vec3::new(x, y, 5.0), Vec3::new(0.0, 0.0, -1.0)),
+                &scene,
+            )
+        };
+        assert_eq!(color_at(-2.0, 0.0), color_at(2.0, 0.0));
+        assert_ne!(color_at(-2.0, 0.0), color_at(0.0, 3.0));
+        assert_eq!(color_at(0.0, -3.0), Color::zero());
+    }
+}
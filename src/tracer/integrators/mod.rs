@@ -0,0 +1,13 @@
+pub mod ambient_occlusion;
+pub mod bidirectional;
+pub mod debug;
+pub mod path;
+pub mod spectral_path;
+pub mod whitted;
+
+pub use ambient_occlusion::*;
+pub use bidirectional::*;
+pub use debug::*;
+pub use path::*;
+pub use spectral_path::*;
+pub use whitted::*;
@@ -0,0 +1,382 @@
+use crate::{
+    power_heuristic, random_f64, Aovs, Color, HitRecord, Hittable, Integrator, Interval,
+    MediumStack, Ray, Scene,
+};
+
+/// Unidirectional path tracer with next-event estimation, multiple importance sampling
+/// and Russian roulette. Paths are traced iteratively, so deep paths cannot overflow
+/// the stack.
+pub struct PathIntegrator {
+    pub max_depth: i32,      // Maximum number of ray bounces into scene
+    pub roulette_depth: i32, // Bounces before Russian roulette may end a path
+}
+
+impl PathIntegrator {
+    pub fn new(max_depth: i32) -> Self {
+        Self {
+            max_depth,
+            roulette_depth: 3,
+        }
+    }
+
+    pub fn with_roulette_depth(mut self, roulette_depth: i32) -> Self {
+        self.roulette_depth = roulette_depth;
+        self
+    }
+
+    /// Light arriving at `hit_record` directly from the scene's lights and the
+    /// environment, one sample each, plus every delta light.
+    fn direct_light(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        scene: &Scene,
+        media: &MediumStack,
+    ) -> Color {
+        self.sample_lights(ray, hit_record, scene, media)
+            + self.sample_environment(ray, hit_record, scene, media)
+            + scene.delta_lighting(ray, hit_record)
+    }
+
+    /// Samples a point on one of the lights, weighted against BSDF sampling with the
+    /// power heuristic.
+    fn sample_lights(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        scene: &Scene,
+        media: &MediumStack,
+    ) -> Color {
+        let Some(direction) = scene.sample_light(hit_record.p) else {
+            return Color::zero();
+        };
+        let bsdf = hit_record.material.eval(ray, hit_record, direction);
+        let pdf = scene.light_pdf(hit_record.p, direction);
+        if bsdf.near_zero() || pdf <= 0.0 {
+            return Color::zero();
+        }
+
+        let mut shadow_ray = Ray::with_time(hit_record.p, direction, ray.time);
+        shadow_ray.wavelength = ray.wavelength;
+        let Some(light) = scene.hit_light(&shadow_ray, Interval::new(0.001, f64::INFINITY)) else {
+            return Color::zero();
+        };
+
+        // Anything between the point and the light, including participating media,
+        // shadows it.
+        let visibility = scene
+            .world
+            .transmittance(&shadow_ray, Interval::new(0.001, light.t - 0.001));
+        if visibility <= 0.0 {
+            return Color::zero();
+        }
+        let absorption = media.transmittance(light.t * direction.length());
+
+        let weight = power_heuristic(pdf, hit_record.material.pdf(ray, hit_record, direction));
+        weight * visibility * absorption * bsdf * light.material.emitted(&shadow_ray, &light) / pdf
+    }
+
+    /// Samples a direction towards the environment, if it supports importance sampling,
+    /// weighted against BSDF sampling with the power heuristic.
+    fn sample_environment(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        scene: &Scene,
+        media: &MediumStack,
+    ) -> Color {
+        let Some((direction, pdf)) = scene.environment.sample() else {
+            return Color::zero();
+        };
+        let bsdf = hit_record.material.eval(ray, hit_record, direction);
+        if bsdf.near_zero() || pdf <= 0.0 {
+            return Color::zero();
+        }
+
+        let mut shadow_ray = Ray::with_time(hit_record.p, direction, ray.time);
+        shadow_ray.wavelength = ray.wavelength;
+        let visibility = scene
+            .world
+            .transmittance(&shadow_ray, Interval::new(0.001, f64::INFINITY));
+        if visibility <= 0.0 {
+            return Color::zero();
+        }
+        let absorption = media.transmittance(f64::INFINITY);
+
+        let weight = power_heuristic(pdf, hit_record.material.pdf(ray, hit_record, direction));
+        weight * visibility * absorption * bsdf * scene.environment.value(direction) / pdf
+    }
+}
+
+impl Default for PathIntegrator {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl Integrator for PathIntegrator {
+    fn li(&self, ray: &Ray, scene: &Scene) -> Color {
+        self.li_aovs(ray, scene, &mut Aovs::default())
+    }
+
+    fn li_aovs(&self, ray: &Ray, scene: &Scene, aovs: &mut Aovs) -> Color {
+        let mut ray = *ray;
+        let mut radiance = Color::zero();
+        let mut throughput = Color::ones(); // Product of path weights from the camera
+        let mut media = MediumStack::new();
+        // Density with which the current ray was picked by BSDF sampling, when the
+        // previous vertex also sampled the lights and the two must be weighted.
+        let mut bsdf_pdf: Option<f64> = None;
+        // Whether the camera ray's first bounce was specular, which decides the pass
+        // that everything after it lands in.
+        let mut specular_path = false;
+
+        for depth in 0..self.max_depth {
+            let Some(hr) = scene.world.hit(&ray, Interval::new(0.001, f64::INFINITY)) else {
+                let weight = bsdf_pdf.map_or(1.0, |pdf| {
+                    power_heuristic(pdf, scene.environment.pdf(ray.direction))
+                });
+                let contribution = throughput
+                    * media.transmittance(f64::INFINITY)
+                    * weight
+                    * scene.environment.value(ray.direction);
+                aovs.add_lighting(contribution, depth, specular_path);
+                radiance += contribution;
+                break;
+            };
+
+            if depth == 0 {
+                aovs.record_first_hit(&ray, &hr);
+            }
+
+            // Light reaching the hit point was filtered by whatever medium the ray crossed.
+            throughput = throughput * media.transmittance(hr.t * ray.direction.length());
+
+            let mut emitted = hr.material.emitted(&ray, &hr);
+            if let Some(pdf) = bsdf_pdf {
+                if !emitted.near_zero() && scene.is_light_hit(&ray, &hr) {
+                    let light_pdf = scene.light_pdf(ray.origin, ray.direction);
+                    emitted = power_heuristic(pdf, light_pdf) * emitted;
+                }
+            }
+            aovs.add_lighting(throughput * emitted, depth, specular_path);
+            radiance += throughput * emitted;
+
+            let Some(mut sr) = hr.material.scatter_in_media(&ray, &hr, &mut media) else {
+                break;
+            };
+            // Once a path has been narrowed to one wavelength it keeps it.
+            sr.scatter_ray.wavelength = sr.scatter_ray.wavelength.or(ray.wavelength);
+            if depth == 0 {
+                specular_path = sr.is_specular;
+            }
+
+            // Delta lobes cannot be evaluated towards a light, so they rely on the
+            // scattered ray finding it.
+            bsdf_pdf = if sr.is_specular {
+                None
+            } else {
+                let direct = throughput * self.direct_light(&ray, &hr, scene, &media);
+                aovs.add_lighting(direct, depth + 1, specular_path);
+                radiance += direct;
+                Some(sr.pdf)
+            };
+
+            throughput = throughput * sr.attenuation;
+
+            // Russian roulette: past the minimum depth, continue with probability equal
+            // to the throughput and boost survivors, so dim paths end early without bias.
+            if depth >= self.roulette_depth {
+                let survival = throughput.x.max(throughput.y).max(throughput.z).min(1.0);
+                if random_f64() >= survival {
+                    break;
+                }
+                throughput = throughput / survival;
+            }
+
+            ray = sr.scatter_ray;
+        }
+
+        radiance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ConstantEnvironment, DiffuseLight, EnvironmentMap, HittableList, Lambertian, Material,
+        MediumEntry, Metal, Quad, RefractiveIndex, ScatterRecord, Sphere, Vec3,
+    };
+
+    /// A diffuse surface that also glows, without being sampled as a light.
+    struct GlowingLambertian {
+        lambertian: Lambertian,
+        emit: Color,
+    }
+
+    impl Material for GlowingLambertian {
+        fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+            self.lambertian.scatter(ray, hit_record)
+        }
+
+        fn eval(&self, ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> Color {
+            self.lambertian.eval(ray, hit_record, scattered)
+        }
+
+        fn pdf(&self, ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> f64 {
+            self.lambertian.pdf(ray, hit_record, scattered)
+        }
+
+        fn emitted(&self, _ray: &Ray, _hit_record: &HitRecord) -> Color {
+            self.emit
+        }
+    }
+
+    /// Mean radiance seen from the center of a closed sphere whose walls reflect half
+    /// the light reaching them and emit `emit`, so every direction sees emit / (1 - 0.5).
+    fn furnace_mean(integrator: &PathIntegrator, emit: f64, samples: usize) -> f64 {
+        let mut world = HittableList::default();
+        world.objects.push(Box::new(Sphere::new(
+            Vec3::zero(),
+            1.0,
+            GlowingLambertian {
+                lambertian: Lambertian::new(Color::new(0.5, 0.5, 0.5)),
+                emit: Color::new(emit, emit, emit),
+            },
+        )));
+        let scene = Scene::new(world, Box::new(ConstantEnvironment::new(Color::zero())));
+
+        let ray = Ray::new(Vec3::zero(), Vec3::new(0.3, 0.4, -1.0));
+        let total: f64 = (0..samples).map(|_| integrator.li(&ray, &scene).y).sum();
+        total / samples as f64
+    }
+
+    #[test]
+    fn test_furnace_with_russian_roulette() {
+        let expected = 2.0 * 0.25;
+        let without_roulette = PathIntegrator::new(40).with_roulette_depth(40);
+        let mean = furnace_mean(&without_roulette, 0.25, 2000);
+        assert!((mean - expected).abs() < 1e-6, "{mean}");
+
+        // Roulette from the first bounce keeps the mean, it only adds noise.
+        let with_roulette = PathIntegrator::new(40).with_roulette_depth(0);
+        let mean = furnace_mean(&with_roulette, 0.25, 20000);
+        assert!((mean - expected).abs() < 0.02, "{mean}");
+    }
+
+    fn floor() -> Quad<Lambertian> {
+        Quad::new(
+            Vec3::new(-5.0, 0.0, -5.0),
+            Vec3::new(0.0, 0.0, 10.0),
+            Vec3::new(10.0, 0.0, 0.0),
+            Lambertian::new(Color::new(0.5, 0.5, 0.5)),
+        )
+    }
+
+    #[test]
+    fn test_mis_weights_sum_to_one() {
+        let mut world = HittableList::default();
+        world.objects.push(Box::new(floor()));
+        world.objects.push(Box::new(Quad::new(
+            Vec3::new(-1.0, 2.0, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            DiffuseLight::new(Color::ones()),
+        )));
+        let scene = Scene::new(world, Box::new(ConstantEnvironment::new(Color::zero())));
+
+        let ray = Ray::new(Vec3::new(0.3, 1.0, 0.2), Vec3::new(0.0, -1.0, 0.0));
+        let hr = scene
+            .world
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        for _ in 0..100 {
+            // Whichever strategy produced the direction, both weigh it consistently.
+            let direction = scene.sample_light(hr.p).unwrap();
+            let light_pdf = scene.light_pdf(hr.p, direction);
+            let bsdf_pdf = hr.material.pdf(&ray, &hr, direction);
+            assert!(light_pdf > 0.0 && bsdf_pdf > 0.0);
+            let sum = power_heuristic(light_pdf, bsdf_pdf) + power_heuristic(bsdf_pdf, light_pdf);
+            assert!((sum - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_environment_sample_is_absorbed_by_media() {
+        let mut world = HittableList::default();
+        world.objects.push(Box::new(floor()));
+        let environment = EnvironmentMap::new(2, 1, vec![Color::ones(); 2]);
+        let scene = Scene::new(world, Box::new(environment));
+
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let hr = scene
+            .world
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        let integrator = PathIntegrator::default();
+
+        let clear = MediumStack::new();
+        let mut lit = Color::zero();
+        for _ in 0..100 {
+            lit += integrator.sample_environment(&ray, &hr, &scene, &clear);
+        }
+        assert!(lit.x > 0.0);
+
+        let mut murky = MediumStack::new();
+        murky.push(MediumEntry {
+            id: 0,
+            priority: 0,
+            refraction_index: RefractiveIndex::Constant(1.33),
+            absorption: Color::new(1.0, 0.0, 1.0),
+        });
+        for _ in 0..100 {
+            let color = integrator.sample_environment(&ray, &hr, &scene, &murky);
+            assert_eq!((color.x, color.z), (0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_lighting_passes_add_up() {
+        let mut world = HittableList::default();
+        world.objects.push(Box::new(floor()));
+        world.objects.push(Box::new(Quad::new(
+            Vec3::new(-1.0, 3.0, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            DiffuseLight::new(Color::new(4.0, 4.0, 4.0)),
+        )));
+        world.objects.push(Box::new(Sphere::new(
+            Vec3::new(1.0, 1.0, 0.0),
+            0.5,
+            Metal::new(Color::new(0.9, 0.8, 0.7), 0.0),
+        )));
+        let environment = EnvironmentMap::new(2, 1, vec![Color::new(0.2, 0.3, 0.4); 2]);
+        let scene = Scene::new(world, Box::new(environment));
+        let integrator = PathIntegrator::new(8).with_roulette_depth(2);
+
+        let origin = Vec3::new(0.0, 1.5, 4.0);
+        let mut passes_seen = [false; 4];
+        for i in 0..400 {
+            let x = (i % 20) as f64 / 10.0 - 1.0;
+            let y = (i / 20) as f64 / 10.0 - 1.5;
+            let ray = Ray::new(origin, Vec3::new(x, y, -4.0));
+            let mut aovs = Aovs::default();
+            let color = integrator.li_aovs(&ray, &scene, &mut aovs);
+
+            let passes = [
+                aovs.emission,
+                aovs.direct_diffuse,
+                aovs.indirect_diffuse,
+                aovs.specular,
+            ];
+            let sum = passes.iter().fold(Color::zero(), |sum, &pass| sum + pass);
+            assert!((sum - color).length() <= 1e-9 * color.length().max(1.0));
+            for (seen, pass) in passes_seen.iter_mut().zip(passes) {
+                *seen |= !pass.near_zero();
+            }
+        }
+        // Every pass was exercised.
+        assert_eq!(passes_seen, [true; 4]);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{
+    random_f64, rgb_to_illuminant, rgb_to_reflectance, rgb_to_unbounded, xyz_to_linear_srgb, Color,
+    Hittable, Integrator, Interval, MediumStack, Ray, SampledSpectrum, SampledWavelengths, Scene,
+};
+
+/// Path tracer that carries sampled wavelengths instead of RGB, converting to XYZ and
+/// then sRGB per sample. RGB albedos are upsampled to the path's wavelengths at each
+/// bounce. Area lights are only found by scattering, without next-event estimation;
+/// delta lights, which scattering can never hit, are sampled directly and their RGB
+/// contribution upsampled like the other RGB terms.
+pub struct SpectralPathIntegrator {
+    pub max_depth: i32,      // Maximum number of ray bounces into scene
+    pub roulette_depth: i32, // Bounces before Russian roulette may end a path
+}
+
+impl SpectralPathIntegrator {
+    pub fn new(max_depth: i32) -> Self {
+        Self {
+            max_depth,
+            roulette_depth: 3,
+        }
+    }
+
+    pub fn with_roulette_depth(mut self, roulette_depth: i32) -> Self {
+        self.roulette_depth = roulette_depth;
+        self
+    }
+}
+
+impl Default for SpectralPathIntegrator {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl Integrator for SpectralPathIntegrator {
+    fn li(&self, ray: &Ray, scene: &Scene) -> Color {
+        let mut wavelengths = SampledWavelengths::sample_uniform(random_f64());
+        let mut ray = *ray;
+        ray.wavelength = Some(wavelengths.hero());
+
+        let mut radiance = SampledSpectrum::zero();
+        let mut throughput = SampledSpectrum::ones();
+        let mut media = MediumStack::new();
+
+        for depth in 0..self.max_depth {
+            let Some(hr) = scene.world.hit(&ray, Interval::new(0.001, f64::INFINITY)) else {
+                radiance += throughput
+                    * scene
+                        .environment
+                        .value_spectrum(ray.direction, &wavelengths);
+                break;
+            };
+
+            // Upsample the medium's absorption coefficient, which is the same all along
+            // the medium, rather than the transmittance, which changes with distance.
+            if let Some(medium) = media.top() {
+                let distance = hr.t * ray.direction.length();
+                let sigma = rgb_to_unbounded(medium.absorption, &wavelengths);
+                throughput *= SampledSpectrum(sigma.0.map(|s| (-s * distance).exp()));
+            }
+            radiance += throughput * hr.material.emitted_spectrum(&ray, &hr, &wavelengths);
+            radiance +=
+                throughput * rgb_to_illuminant(scene.delta_lighting(&ray, &hr), &wavelengths);
+
+            let Some(mut sr) = hr.material.scatter_in_media(&ray, &hr, &mut media) else {
+                break;
+            };
+            // A wavelength on the scattered ray means its direction depended on it
+            // (dispersion), so only the hero wavelength remains valid.
+            if sr.scatter_ray.wavelength.is_some() {
+                wavelengths.terminate_secondary();
+            }
+            sr.scatter_ray.wavelength = ray.wavelength;
+            throughput *= rgb_to_reflectance(sr.attenuation, &wavelengths);
+
+            if depth >= self.roulette_depth {
+                let survival = throughput.max_value().min(1.0);
+                if random_f64() >= survival {
+                    break;
+                }
+                throughput = throughput / survival;
+            }
+
+            ray = sr.scatter_ray;
+        }
+
+        xyz_to_linear_srgb(wavelengths.to_xyz(&radiance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::{ConstantEnvironment, DeltaLight, HittableList, Lambertian, Plane, Vec3};
+
+    #[test]
+    fn test_delta_lights() {
+        let mut world = HittableList::default();
+        world.objects.push(Box::new(Plane::new(
+            Vec3::zero(),
+            Vec3::new(0.0, 1.0, 0.0),
+            Lambertian::new(Color::new(0.5, 0.5, 0.5)),
+        )));
+        let scene = Scene::new(world, Box::new(ConstantEnvironment::new(Color::zero())))
+            .with_delta_light(DeltaLight::point(Vec3::new(0.0, 1.0, 0.0), Color::ones()));
+
+        // Straight below the light, 0.5 / pi of its unit irradiance comes back.
+        let ray = Ray::new(Vec3::new(0.0, 1.0, 1.0), Vec3::new(0.0, -1.0, -1.0));
+        let integrator = SpectralPathIntegrator::default();
+        let n = 4000;
+        let mean =
+            (0..n).fold(Color::zero(), |sum, _| sum + integrator.li(&ray, &scene)) / n as f64;
+        let expected = 0.5 / PI;
+        for channel in [mean.x, mean.y, mean.z] {
+            assert!((channel - expected).abs() < 0.05 * expected, "{mean:?}");
+        }
+    }
+}
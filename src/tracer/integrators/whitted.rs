@@ -0,0 +1,91 @@
+use crate::{Color, Hittable, Integrator, Interval, Ray, Scene};
+
+/// Classic Whitted ray tracer: delta lights with hard shadows on diffuse surfaces, and
+/// both the mirror and refraction rays followed at perfectly specular ones. Nothing is
+/// sampled at random, so a single sample per pixel gives a noise-free preview.
+///
+/// Area lights and the environment are only seen directly or through specular
+/// surfaces; they do not light diffuse ones.
+pub struct WhittedIntegrator {
+    pub max_depth: i32, // Maximum number of specular bounces
+}
+
+impl WhittedIntegrator {
+    pub fn new(max_depth: i32) -> Self {
+        Self { max_depth }
+    }
+
+    fn trace(&self, ray: &Ray, scene: &Scene, depth: i32) -> Color {
+        if depth <= 0 {
+            return Color::zero();
+        }
+        let Some(hr) = scene.world.hit(ray, Interval::new(0.001, f64::INFINITY)) else {
+            return scene.environment.value(ray.direction);
+        };
+
+        let mut color = hr.material.emitted(ray, &hr);
+        let lobes = hr.material.specular_lobes(ray, &hr);
+        if lobes.is_empty() {
+            color += scene.delta_lighting(ray, &hr);
+        }
+        for lobe in lobes {
+            color += lobe.attenuation * self.trace(&lobe.scatter_ray, scene, depth - 1);
+        }
+        color
+    }
+}
+
+impl Default for WhittedIntegrator {
+    fn default() -> Self {
+        Self::new(8)
+    }
+}
+
+impl Integrator for WhittedIntegrator {
+    fn li(&self, ray: &Ray, scene: &Scene) -> Color {
+        self.trace(ray, scene, self.max_depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::{ConstantEnvironment, DeltaLight, HittableList, Lambertian, Plane, Vec3};
+
+    #[test]
+    fn test_point_lit_lambertian() {
+        let albedo = 0.6;
+        let intensity = 10.0;
+        let mut world = HittableList::default();
+        world.objects.push(Box::new(Plane::new(
+            Vec3::zero(),
+            Vec3::new(0.0, 1.0, 0.0),
+            Lambertian::new(Color::new(albedo, albedo, albedo)),
+        )));
+        let light_position = Vec3::new(0.0, 2.0, 0.0);
+        let scene =
+            Scene::new(world, Box::new(ConstantEnvironment::new(Color::zero()))).with_delta_light(
+                DeltaLight::point(light_position, Color::new(intensity, intensity, intensity)),
+            );
+
+        let integrator = WhittedIntegrator::default();
+        for &x in &[0.0, 1.0, 3.0] {
+            let p = Vec3::new(x, 0.0, 0.5);
+            let ray = Ray::new(Vec3::new(x, 1.0, 2.0), p - Vec3::new(x, 1.0, 2.0));
+            let color = integrator.li(&ray, &scene);
+
+            // Radiance off a Lambertian surface is albedo / pi * cos(theta) * E.
+            let to_light = light_position - p;
+            let cosine = to_light.unit().y;
+            let irradiance = intensity / to_light.length_squard();
+            let expected = albedo / PI * cosine * irradiance;
+            assert!(
+                (color.x - expected).abs() < 1e-9,
+                "{} vs {expected}",
+                color.x
+            );
+        }
+    }
+}
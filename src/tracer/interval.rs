@@ -1,3 +1,4 @@
+#[derive(Clone, Copy, Debug)]
 pub struct Interval {
     pub min: f64,
     pub max: f64,
@@ -8,6 +9,14 @@ impl Interval {
         Interval { min, max }
     }
 
+    /// Create the interval tightly enclosing the two input intervals.
+    pub fn enclosing(a: &Interval, b: &Interval) -> Self {
+        Interval {
+            min: a.min.min(b.min),
+            max: a.max.max(b.max),
+        }
+    }
+
     pub fn size(&self) -> f64 {
         self.max - self.min
     }
@@ -22,25 +31,30 @@ impl Interval {
 
     pub fn clamp(&self, val: f64) -> f64 {
         if val < self.min {
-            return val;
+            return self.min;
         }
         if val > self.max {
-            return val;
+            return self.max;
         }
         val
     }
 
+    pub fn expand(&self, delta: f64) -> Self {
+        let padding = delta / 2.0;
+        Self::new(self.min - padding, self.max + padding)
+    }
+
     pub fn empty() -> Self {
         Self {
-            min: std::f64::INFINITY,
-            max: std::f64::NEG_INFINITY,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
         }
     }
 
     pub fn universe() -> Self {
         Self {
-            min: std::f64::NEG_INFINITY,
-            max: std::f64::INFINITY,
+            min: f64::NEG_INFINITY,
+            max: f64::INFINITY,
         }
     }
 }
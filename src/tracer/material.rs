@@ -1,5 +1,136 @@
-use crate::{HitRecord, Ray, ScatterRecord};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::{
+    rgb_to_illuminant, HitRecord, MediumStack, Ray, SampledSpectrum, SampledWavelengths,
+    ScatterRecord, Vec3,
+};
+
+static NEXT_MATERIAL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A fresh identifier for a newly constructed material. Ids are handed out in
+/// construction order, so they do not depend on where the material ends up in memory.
+pub fn next_material_id() -> usize {
+    NEXT_MATERIAL_ID.fetch_add(1, Ordering::Relaxed)
+}
 
 pub trait Material {
+    /// Identifies the material, so that objects sharing it can be told apart from
+    /// objects that merely look alike. Defaults to the material's address; the built-in
+    /// materials use `next_material_id`, which also stays the same from run to run.
+    fn material_id(&self) -> usize {
+        self as *const Self as *const () as usize
+    }
+
     fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord>;
+
+    /// Like `scatter`, but aware of the nested media the path is inside. Materials that
+    /// bound a medium update `media` when the path crosses into or out of them.
+    fn scatter_in_media(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        _media: &mut MediumStack,
+    ) -> Option<ScatterRecord> {
+        self.scatter(ray, hit_record)
+    }
+
+    /// BSDF times the cosine factor for light leaving along `scattered` after arriving
+    /// along `ray`. Zero for specular materials, whose scattering is a delta distribution.
+    fn eval(&self, _ray: &Ray, _hit_record: &HitRecord, _scattered: Vec3) -> Vec3 {
+        Vec3::zero()
+    }
+
+    /// Solid-angle density with which `scatter` would pick `scattered`.
+    fn pdf(&self, _ray: &Ray, _hit_record: &HitRecord, _scattered: Vec3) -> f64 {
+        0.0
+    }
+
+    fn emitted(&self, _ray: &Ray, _hit_record: &HitRecord) -> Vec3 {
+        Vec3::zero()
+    }
+
+    /// Every perfectly specular direction light can leave the hit point in, each
+    /// weighted by the fraction of light going that way. Unlike `scatter`, nothing is
+    /// chosen at random. Empty for materials that are not perfectly specular.
+    fn specular_lobes(&self, _ray: &Ray, _hit_record: &HitRecord) -> Vec<ScatterRecord> {
+        Vec::new()
+    }
+
+    /// Overall reflectance color at the hit point, as used for albedo passes and
+    /// denoiser guides. Black for materials that do not scatter.
+    fn albedo(&self, _hit_record: &HitRecord) -> Vec3 {
+        Vec3::zero()
+    }
+
+    /// Whether surfaces with this material should be sampled as light sources.
+    fn is_emissive(&self) -> bool {
+        false
+    }
+
+    /// Emitted spectral radiance at the path's wavelengths. Defaults to upsampling the
+    /// RGB emission.
+    fn emitted_spectrum(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        wavelengths: &SampledWavelengths,
+    ) -> SampledSpectrum {
+        rgb_to_illuminant(self.emitted(ray, hit_record), wavelengths)
+    }
+}
+
+/// Lets several objects share one material.
+impl<M: Material + ?Sized> Material for Arc<M> {
+    fn material_id(&self) -> usize {
+        (**self).material_id()
+    }
+
+    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        (**self).scatter(ray, hit_record)
+    }
+
+    fn scatter_in_media(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        media: &mut MediumStack,
+    ) -> Option<ScatterRecord> {
+        (**self).scatter_in_media(ray, hit_record, media)
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> Vec3 {
+        (**self).eval(ray, hit_record, scattered)
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> f64 {
+        (**self).pdf(ray, hit_record, scattered)
+    }
+
+    fn emitted(&self, ray: &Ray, hit_record: &HitRecord) -> Vec3 {
+        (**self).emitted(ray, hit_record)
+    }
+
+    fn specular_lobes(&self, ray: &Ray, hit_record: &HitRecord) -> Vec<ScatterRecord> {
+        (**self).specular_lobes(ray, hit_record)
+    }
+
+    fn albedo(&self, hit_record: &HitRecord) -> Vec3 {
+        (**self).albedo(hit_record)
+    }
+
+    fn is_emissive(&self) -> bool {
+        (**self).is_emissive()
+    }
+
+    fn emitted_spectrum(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        wavelengths: &SampledWavelengths,
+    ) -> SampledSpectrum {
+        (**self).emitted_spectrum(ray, hit_record, wavelengths)
+    }
 }
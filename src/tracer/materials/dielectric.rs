@@ -1,12 +1,103 @@
-use crate::{random_f64_range, reflect, refract, Material, Ray, ScatterRecord, Vec3};
+use crate::{
+    next_material_id, random_f64, reflect, refract, sample_visible_wavelength, Color, HitRecord,
+    Material, MediumEntry, MediumStack, Ray, ScatterRecord, Vec3, SPECTRUM_SAMPLES,
+};
 
+/// Wavelength at which dispersive materials report their nominal index (sodium d-line).
+const D_LINE: f64 = 587.6;
+
+/// Index of refraction as a function of wavelength.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RefractiveIndex {
+    Constant(f64),
+    /// Cauchy's equation `a + b / lambda^2`, with lambda in micrometers.
+    Cauchy {
+        a: f64,
+        b: f64,
+    },
+    /// The Sellmeier equation `n^2 = 1 + sum b_i lambda^2 / (lambda^2 - c_i)`, with
+    /// lambda in micrometers and `c` in square micrometers.
+    Sellmeier {
+        b: [f64; 3],
+        c: [f64; 3],
+    },
+}
+
+impl RefractiveIndex {
+    /// Schott N-BK7 crown glass.
+    pub const BK7: Self = Self::Sellmeier {
+        b: [1.039_612_12, 0.231_792_344, 1.010_469_45],
+        c: [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+    };
+    /// Diamond, which owes its fire to unusually strong dispersion.
+    pub const DIAMOND: Self = Self::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030_625, 0.011_236, 0.0],
+    };
+
+    /// Index at `wavelength` nanometers, or at the d-line if the path carries no
+    /// wavelength.
+    pub fn at(&self, wavelength: Option<f64>) -> f64 {
+        let micrometers = wavelength.unwrap_or(D_LINE) / 1000.0;
+        let l2 = micrometers * micrometers;
+        match *self {
+            Self::Constant(n) => n,
+            Self::Cauchy { a, b } => a + b / l2,
+            Self::Sellmeier { b, c } => {
+                let sum: f64 = (0..3).map(|i| b[i] * l2 / (l2 - c[i])).sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        !matches!(self, Self::Constant(_))
+    }
+}
+
+/// Glass-like material that reflects or refracts according to Fresnel's equations.
+///
+/// Light travelling through the interior is attenuated following the Beer-Lambert law,
+/// so thick parts of tinted glass or liquid look darker than thin ones. When dielectrics
+/// overlap, the one with the higher `priority` owns the shared region.
+///
+/// With a dispersive index, an RGB path meeting the surface is split over a randomly
+/// sampled hero wavelength and evenly spaced companions, like `SampledWavelengths` in
+/// spectral mode. Reflection sends them all the same way, so the path stays RGB; the
+/// first refraction commits it to the hero wavelength, which it carries from then on,
+/// so white light fans out into a spectrum.
 pub struct Dielectric {
-    pub refraction_index: f64,
+    pub refraction_index: RefractiveIndex,
+    pub absorption: Color, // Absorption coefficient per unit distance, per channel
+    pub priority: u32,
+    id: usize, // Also tells this medium apart from others on a MediumStack
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f64) -> Self {
-        Self { refraction_index }
+        Self::with_index(RefractiveIndex::Constant(refraction_index))
+    }
+
+    pub fn with_index(refraction_index: RefractiveIndex) -> Self {
+        Self {
+            refraction_index,
+            absorption: Color::zero(),
+            priority: 0,
+            id: next_material_id(),
+        }
+    }
+
+    /// Tints the interior so that light crossing `1 / density` units of material is
+    /// filtered down to `color`.
+    pub fn with_absorption(mut self, color: Color, density: f64) -> Self {
+        let channel = |c: f64| -c.clamp(1e-6, 1.0).ln() * density;
+        self.absorption = Color::new(channel(color.x), channel(color.y), channel(color.z));
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
     }
 
     fn reflectance(&self, cosine: f64, refraction_index: f64) -> f64 {
@@ -16,35 +107,404 @@ impl Dielectric {
 
         r0 + (1.0 - r0) * (1.0 - cosine).powf(5.0)
     }
+
+    /// Picks the reflected or refracted direction, given the relative index at a
+    /// wavelength. Returns the direction, whether the ray refracted across the
+    /// interface, the wavelength the new direction only holds for, and the weight the
+    /// path must carry from now on.
+    fn bend(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        dispersive: bool,
+        ri_at: impl Fn(Option<f64>) -> f64,
+    ) -> (Vec3, bool, Option<f64>, Color) {
+        let unit_direction = ray.direction.unit();
+        let cos_theta = (-unit_direction).dot(hit_record.normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let reflectance = |ri: f64| {
+            if ri * sin_theta > 1.0 {
+                1.0
+            } else {
+                self.reflectance(cos_theta, ri)
+            }
+        };
+        let reflected = reflect(unit_direction, hit_record.normal);
+
+        if ray.wavelength.is_some() || !dispersive {
+            let ri = ri_at(ray.wavelength);
+            let wavelength = ray.wavelength.filter(|_| dispersive);
+            return if reflectance(ri) > random_f64() {
+                (reflected, false, wavelength, Color::ones())
+            } else {
+                let refracted = refract(unit_direction, hit_record.normal, ri);
+                (refracted, true, wavelength, Color::ones())
+            };
+        }
+
+        // An RGB path: the hero decides, and the companions share its reflection.
+        let u = random_f64();
+        let wavelengths: [_; SPECTRUM_SAMPLES] = std::array::from_fn(|i| {
+            sample_visible_wavelength((u + i as f64 / SPECTRUM_SAMPLES as f64).fract())
+        });
+        let reflectances = wavelengths.map(|(lambda, _)| reflectance(ri_at(Some(lambda))));
+        let (hero, hero_weight) = wavelengths[0];
+        if reflectances[0] > random_f64() {
+            let sum = wavelengths
+                .iter()
+                .zip(reflectances)
+                .fold(Color::zero(), |sum, (&(_, weight), r)| sum + weight * r);
+            let weight = sum / (SPECTRUM_SAMPLES as f64 * reflectances[0]);
+            (reflected, false, None, weight)
+        } else {
+            let refracted = refract(unit_direction, hit_record.normal, ri_at(Some(hero)));
+            (refracted, true, Some(hero), hero_weight)
+        }
+    }
+
+    /// A back-face hit means the ray has just crossed the interior, from its origin on
+    /// the surface to here, and was absorbed along the way.
+    fn interior_transmittance(&self, ray: &Ray, hit_record: &HitRecord) -> Color {
+        if hit_record.front_face {
+            return Color::ones();
+        }
+        let distance = hit_record.t * ray.direction.length();
+        Color::new(
+            (-self.absorption.x * distance).exp(),
+            (-self.absorption.y * distance).exp(),
+            (-self.absorption.z * distance).exp(),
+        )
+    }
+
+    fn medium_entry(&self) -> MediumEntry {
+        MediumEntry {
+            id: self.id,
+            priority: self.priority,
+            refraction_index: self.refraction_index,
+            absorption: self.absorption,
+        }
+    }
 }
 
 impl Material for Dielectric {
-    fn scatter(
-        &self,
-        ray: &crate::Ray,
-        hit_record: &crate::HitRecord,
-    ) -> Option<crate::ScatterRecord> {
+    fn material_id(&self) -> usize {
+        self.id
+    }
+
+    fn albedo(&self, _hit_record: &HitRecord) -> Color {
+        Color::ones()
+    }
+
+    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let ri_at = |wavelength| {
+            let refraction_index = self.refraction_index.at(wavelength);
+            if hit_record.front_face {
+                1.0 / refraction_index
+            } else {
+                refraction_index
+            }
+        };
+        let dispersive = self.refraction_index.is_dispersive();
+        let (direction, _, wavelength, weight) = self.bend(ray, hit_record, dispersive, ri_at);
+
+        let attenuation = self.interior_transmittance(ray, hit_record);
+
+        // A wavelength on the new ray tells the caller its direction only holds for it.
+        let mut scatter_ray = Ray::with_time(hit_record.p, direction, ray.time);
+        scatter_ray.wavelength = wavelength;
+        Some(ScatterRecord::specular(weight * attenuation, scatter_ray))
+    }
+
+    /// Both the reflected and the refracted ray, weighted by Schlick's approximation.
+    /// Overlapping media are not resolved here; the ray is assumed to go between this
+    /// material and vacuum.
+    fn specular_lobes(&self, ray: &Ray, hit_record: &HitRecord) -> Vec<ScatterRecord> {
+        let refraction_index = self.refraction_index.at(ray.wavelength);
         let ri = if hit_record.front_face {
-            1.0 / self.refraction_index
+            1.0 / refraction_index
         } else {
-            self.refraction_index
+            refraction_index
         };
+        let transmittance = self.interior_transmittance(ray, hit_record);
 
-        let uint_direction = ray.direction.unit();
-        let cos_theta = (-uint_direction).dot(hit_record.normal).min(1.0);
+        let unit_direction = ray.direction.unit();
+        let cos_theta = (-unit_direction).dot(hit_record.normal).min(1.0);
         let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let reflectance = if ri * sin_theta > 1.0 {
+            1.0
+        } else {
+            self.reflectance(cos_theta, ri)
+        };
+
+        let mut lobes = vec![ScatterRecord::specular(
+            reflectance * transmittance,
+            Ray::with_time(
+                hit_record.p,
+                reflect(unit_direction, hit_record.normal),
+                ray.time,
+            ),
+        )];
+        if reflectance < 1.0 {
+            lobes.push(ScatterRecord::specular(
+                (1.0 - reflectance) * transmittance,
+                Ray::with_time(
+                    hit_record.p,
+                    refract(unit_direction, hit_record.normal, ri),
+                    ray.time,
+                ),
+            ));
+        }
+        for lobe in &mut lobes {
+            lobe.scatter_ray.wavelength = ray.wavelength;
+        }
+        lobes
+    }
 
-        let cannot_refract = ri * sin_theta > 1.0;
-        let direction =
-            if cannot_refract || self.reflectance(cos_theta, ri) > random_f64_range(0.0, 1.0) {
-                reflect(uint_direction, hit_record.normal)
+    /// Absorption along the path is left to the caller, via `MediumStack::transmittance`,
+    /// since the medium the ray crossed is not necessarily this one.
+    fn scatter_in_media(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        media: &mut MediumStack,
+    ) -> Option<ScatterRecord> {
+        let entry = self.medium_entry();
+        let pass_through = || {
+            Some(ScatterRecord::specular(
+                Vec3::ones(),
+                Ray::with_time(hit_record.p, ray.direction, ray.time),
+            ))
+        };
+
+        let leaving = if hit_record.front_face {
+            // Entering a region already owned by a higher-priority medium is a false
+            // interface: note that we are inside, but keep going straight.
+            if media.top().is_some_and(|top| top.priority > self.priority) {
+                media.push(entry);
+                return pass_through();
+            }
+            false
+        } else if media.contains(entry.id) {
+            // Likewise, leaving a medium we are not currently in is a false interface.
+            if media.top().map(|top| top.id) != Some(entry.id) {
+                media.remove(entry.id);
+                return pass_through();
+            }
+            true
+        } else {
+            // The path started inside this object; assume it is surrounded by the
+            // current medium.
+            false
+        };
+
+        let dispersive = self.refraction_index.is_dispersive() || media.is_dispersive();
+        let ri_at = |wavelength| {
+            let outside = if leaving {
+                media.refraction_index_without(entry.id, wavelength)
             } else {
-                refract(uint_direction, hit_record.normal, ri)
+                media.refraction_index(wavelength)
             };
+            let inside = self.refraction_index.at(wavelength);
+            if hit_record.front_face {
+                outside / inside
+            } else {
+                inside / outside
+            }
+        };
+        let (direction, refracted, wavelength, weight) =
+            self.bend(ray, hit_record, dispersive, ri_at);
+        if refracted {
+            if hit_record.front_face {
+                media.push(entry);
+            } else {
+                media.remove(entry.id);
+            }
+        }
+
+        let mut scatter_ray = Ray::with_time(hit_record.p, direction, ray.time);
+        scatter_ray.wavelength = wavelength;
+        Some(ScatterRecord::specular(weight, scatter_ray))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LAMBDA_MAX, LAMBDA_MIN};
+
+    #[test]
+    fn test_refractive_index() {
+        assert_eq!(RefractiveIndex::Constant(1.5).at(Some(400.0)), 1.5);
+        assert!((RefractiveIndex::BK7.at(None) - 1.5168).abs() < 1e-4);
+        assert!((RefractiveIndex::DIAMOND.at(None) - 2.417).abs() < 2e-3);
+
+        // Normal dispersion: blue bends more than red.
+        let cauchy = RefractiveIndex::Cauchy {
+            a: 1.5046,
+            b: 0.0042,
+        };
+        assert!(cauchy.at(Some(450.0)) > cauchy.at(Some(650.0)));
+    }
+
+    fn hit_record(material: &Dielectric, t: f64, front_face: bool) -> HitRecord<'_> {
+        HitRecord {
+            normal: Vec3::new(0.0, 0.0, 1.0),
+            p: Vec3::zero(),
+            t,
+            u: 0.0,
+            v: 0.0,
+            front_face,
+            material,
+        }
+    }
+
+    #[test]
+    fn test_interior_transmittance() {
+        let glass = Dielectric::new(1.5).with_absorption(Color::new(0.5, 0.25, 1.0), 1.0);
+        let sigma = glass.absorption;
+        assert!((sigma.x - 2f64.ln()).abs() < 1e-12);
+
+        // The direction is not unit length, so distance is t times its length.
+        let ray = Ray::new(Vec3::zero(), Vec3::new(0.0, 0.0, 2.0));
+        let exited = glass.interior_transmittance(&ray, &hit_record(&glass, 1.5, false));
+        let d = 3.0;
+        let expected = Color::new(
+            (-sigma.x * d).exp(),
+            (-sigma.y * d).exp(),
+            (-sigma.z * d).exp(),
+        );
+        assert!((exited - expected).length() < 1e-12);
+        assert!((exited.x - 0.125).abs() < 1e-12);
+        assert!((exited.z - 1.0).abs() < 1e-12);
+
+        let entered = glass.interior_transmittance(&ray, &hit_record(&glass, 1.5, true));
+        assert_eq!(entered, Color::ones());
+    }
+
+    #[test]
+    fn test_medium_ids_are_distinct() {
+        let glass = Dielectric::new(1.5);
+        let water = Dielectric::new(1.33);
+        assert_ne!(glass.medium_entry().id, water.medium_entry().id);
+
+        // Moving a material does not change which medium it stands for.
+        let id = glass.medium_entry().id;
+        let moved = Box::new(glass);
+        assert_eq!(moved.medium_entry().id, id);
+    }
+
+    #[test]
+    fn test_dispersion_commits_on_refraction() {
+        let glass = Dielectric::with_index(RefractiveIndex::BK7);
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.3, 0.0, -1.0));
+        let hr = hit_record(&glass, 1.0, true);
+
+        let (mut reflected, mut refracted) = (false, false);
+        for _ in 0..200 {
+            let record = glass.scatter(&ray, &hr).unwrap();
+            match record.scatter_ray.wavelength {
+                // Reflection sends every wavelength the same way, so the path stays RGB.
+                None => {
+                    assert!(record.scatter_ray.direction.z > 0.0);
+                    reflected = true;
+                }
+                Some(lambda) => {
+                    assert!(record.scatter_ray.direction.z < 0.0);
+                    assert!((LAMBDA_MIN..=LAMBDA_MAX).contains(&lambda));
+                    refracted = true;
+
+                    // Once committed, the path keeps its wavelength and no further
+                    // weight applies.
+                    let committed = Ray {
+                        wavelength: Some(lambda),
+                        ..ray
+                    };
+                    let record = glass.scatter(&committed, &hr).unwrap();
+                    assert_eq!(record.scatter_ray.wavelength, Some(lambda));
+                    assert_eq!(record.attenuation, Color::ones());
+                }
+            }
+        }
+        assert!(reflected && refracted);
+    }
+
+    #[test]
+    fn test_hero_wavelengths_reduce_variance() {
+        // Light arriving at a steep angle on diamond, with the reflection seeing twice as
+        // much light as the refraction.
+        let glass = Dielectric::with_index(RefractiveIndex::DIAMOND);
+        let ray = Ray::new(Vec3::new(-3.0, 0.0, 1.0), Vec3::new(3.0, 0.0, -1.0));
+        let hr = hit_record(&glass, 1.0, true);
+        let estimate = |record: ScatterRecord| {
+            let light = if record.scatter_ray.direction.z > 0.0 {
+                1.0
+            } else {
+                0.5
+            };
+            record.attenuation * light
+        };
+
+        // Collapsing to one wavelength before the interface, as without companions.
+        let single = || {
+            let (lambda, weight) = sample_visible_wavelength(random_f64());
+            let ray = Ray {
+                wavelength: Some(lambda),
+                ..ray
+            };
+            weight * estimate(glass.scatter(&ray, &hr).unwrap())
+        };
+        let hero = || estimate(glass.scatter(&ray, &hr).unwrap());
+
+        let n = 200_000;
+        let statistics = |sample: &dyn Fn() -> Color| {
+            let samples: Vec<_> = (0..n).map(|_| sample()).collect();
+            let mean = samples.iter().fold(Color::zero(), |sum, &c| sum + c) / n as f64;
+            let variance = samples
+                .iter()
+                .map(|&c| (c - mean).length_squard())
+                .sum::<f64>()
+                / n as f64;
+            (mean, variance)
+        };
+        let (single_mean, single_variance) = statistics(&single);
+        let (hero_mean, hero_variance) = statistics(&hero);
+
+        assert!(
+            (hero_mean - single_mean).length() < 0.03,
+            "{hero_mean:?} vs {single_mean:?}"
+        );
+        assert!(
+            hero_variance < 0.6 * single_variance,
+            "{hero_variance} vs {single_variance}"
+        );
+    }
+
+    #[test]
+    fn test_specular_lobes_sum_to_transmittance() {
+        let glass = Dielectric::new(1.5).with_absorption(Color::new(0.5, 0.7, 0.9), 1.0);
+        let sum = |ray: &Ray, hr: &HitRecord| {
+            glass
+                .specular_lobes(ray, hr)
+                .iter()
+                .fold(Color::zero(), |sum, lobe| sum + lobe.attenuation)
+        };
+
+        // Entering at an angle: reflection and refraction split all of the light.
+        let ray = Ray::new(Vec3::new(-1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, -1.0));
+        let hr = hit_record(&glass, 1.0, true);
+        assert_eq!(glass.specular_lobes(&ray, &hr).len(), 2);
+        assert!((sum(&ray, &hr) - Color::ones()).length() < 1e-12);
+
+        // Leaving after crossing the interior, what survived absorption is split.
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 2.0), Vec3::new(0.1, 0.0, -1.0));
+        let hr = hit_record(&glass, 2.0, false);
+        let transmittance = glass.interior_transmittance(&ray, &hr);
+        assert!((sum(&ray, &hr) - transmittance).length() < 1e-12);
 
-        Some(ScatterRecord {
-            attenuation: Vec3::ones(),
-            scatter_ray: Ray::new(hit_record.p, direction),
-        })
+        // Past the critical angle there is only the reflection, which keeps it all.
+        let ray = Ray::new(Vec3::new(2.0, 0.0, 1.0), Vec3::new(-2.0, 0.0, -1.0));
+        let lobes = glass.specular_lobes(&ray, &hr);
+        assert_eq!(lobes.len(), 1);
+        assert!((lobes[0].attenuation - glass.interior_transmittance(&ray, &hr)).length() < 1e-12);
     }
 }
@@ -0,0 +1,70 @@
+use crate::{
+    blackbody_color, blackbody_luminance, next_material_id, planck, rgb_to_illuminant, Color,
+    HitRecord, Material, Ray, SampledSpectrum, SampledWavelengths, ScatterRecord,
+};
+
+pub struct DiffuseLight {
+    pub emit: Color,
+    pub temperature: Option<f64>, // Black-body temperature in Kelvin, for spectral rendering
+    blackbody_scale: f64,         // Scale from Planck's law to the requested luminance
+    id: usize,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        Self {
+            emit,
+            temperature: None,
+            blackbody_scale: 0.0,
+            id: next_material_id(),
+        }
+    }
+
+    /// A black-body emitter at `temperature` Kelvin with luminance `intensity`. Spectral
+    /// rendering uses Planck's law directly instead of an upsampled RGB color.
+    pub fn blackbody(temperature: f64, intensity: f64) -> Self {
+        let luminance = blackbody_luminance(temperature);
+        Self {
+            emit: blackbody_color(temperature) * intensity,
+            temperature: Some(temperature),
+            blackbody_scale: if luminance > 0.0 {
+                intensity / luminance
+            } else {
+                0.0
+            },
+            id: next_material_id(),
+        }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn material_id(&self) -> usize {
+        self.id
+    }
+
+    fn scatter(&self, _ray: &Ray, _hit_record: &HitRecord) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, _ray: &Ray, _hit_record: &HitRecord) -> Color {
+        self.emit
+    }
+
+    fn is_emissive(&self) -> bool {
+        !self.emit.near_zero()
+    }
+
+    fn emitted_spectrum(
+        &self,
+        _ray: &Ray,
+        _hit_record: &HitRecord,
+        wavelengths: &SampledWavelengths,
+    ) -> SampledSpectrum {
+        match self.temperature {
+            Some(temperature) => SampledSpectrum::from_fn(wavelengths, |lambda| {
+                self.blackbody_scale * planck(lambda, temperature)
+            }),
+            None => rgb_to_illuminant(self.emit, wavelengths),
+        }
+    }
+}
@@ -1,24 +1,47 @@
-use crate::{random_uint_vector, HitRecord, Material, Ray, ScatterRecord, Vec3};
+use crate::{
+    next_material_id, Color, CosinePdf, HitRecord, Material, Pdf, Ray, ScatterRecord, Vec3,
+};
 
 pub struct Lambertian {
-    pub albedo: Vec3,
+    pub albedo: Color,
+    id: usize,
 }
 
 impl Lambertian {
-    pub fn new(albedo: Vec3) -> Self {
-        Self { albedo }
+    pub fn new(albedo: Color) -> Self {
+        Self {
+            albedo,
+            id: next_material_id(),
+        }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
-        let mut direction = hit_record.normal + random_uint_vector();
-        if direction.near_zero() {
-            direction = hit_record.normal;
-        }
+    fn material_id(&self) -> usize {
+        self.id
+    }
+
+    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        // Cosine sampling matches the BSDF exactly, so the weight is just the albedo.
+        let pdf = CosinePdf::new(hit_record.normal);
+        let direction = pdf.generate();
         Some(ScatterRecord {
             attenuation: self.albedo,
-            scatter_ray: Ray::new(hit_record.p, direction - hit_record.p),
+            pdf: pdf.value(direction),
+            scatter_ray: Ray::with_time(hit_record.p, direction, ray.time),
+            is_specular: false,
         })
     }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> Color {
+        self.albedo * self.pdf(ray, hit_record, scattered)
+    }
+
+    fn pdf(&self, _ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> f64 {
+        CosinePdf::new(hit_record.normal).value(scattered)
+    }
+
+    fn albedo(&self, _hit_record: &HitRecord) -> Color {
+        self.albedo
+    }
 }
@@ -1,29 +1,126 @@
-use crate::{random_uint_vector, reflect, HitRecord, Material, Ray, ScatterRecord, Vec3};
+use std::f64::consts::PI;
+
+use crate::{
+    next_material_id, random_uint_vector, reflect, Color, HitRecord, Material, Ray, ScatterRecord,
+    Vec3,
+};
 
 pub struct Metal {
-    pub albedo: Vec3,
+    pub albedo: Color,
     pub fuzz: f64,
+    id: usize,
 }
 
 impl Metal {
-    pub fn new(albedo: Vec3, fuzz: f64) -> Self {
+    pub fn new(albedo: Color, fuzz: f64) -> Self {
         let fuzz = if fuzz < 1.0 { fuzz } else { 1.0 };
-        Self { albedo, fuzz }
+        Self {
+            albedo,
+            fuzz,
+            id: next_material_id(),
+        }
+    }
+
+    /// Density of the fuzzed reflection: the direction towards a uniform point on a
+    /// sphere of radius `fuzz` around the tip of the unit mirror direction.
+    fn fuzz_pdf(&self, reflected: Vec3, direction: Vec3) -> f64 {
+        let d = direction.unit();
+        // Points along d at distance t lie on the sphere where
+        // t^2 - 2 t (d.r) + 1 - fuzz^2 = 0.
+        let b = d.dot(reflected);
+        let discriminant = b * b - (1.0 - self.fuzz * self.fuzz);
+        if discriminant <= 0.0 {
+            return 0.0;
+        }
+        let root = discriminant.sqrt();
+        // Each intersection contributes t^2 / |cos| over the sphere's area, where the
+        // cosine between d and the sphere normal is root / fuzz.
+        [b - root, b + root]
+            .iter()
+            .filter(|&&t| t > 0.0)
+            .map(|&t| t * t / (4.0 * PI * self.fuzz * root))
+            .sum()
     }
 }
 
 impl Material for Metal {
+    fn material_id(&self) -> usize {
+        self.id
+    }
+
     fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
-        let mut reflected = reflect(ray.direction, hit_record.normal);
-        reflected = reflected.unit() + (self.fuzz * random_uint_vector());
+        let reflected = reflect(ray.direction, hit_record.normal).unit();
+        if self.fuzz <= 0.0 {
+            let scatter_ray = Ray::with_time(hit_record.p, reflected, ray.time);
+            return Some(ScatterRecord::specular(self.albedo, scatter_ray));
+        }
 
-        let scatter_ray = Ray::new(hit_record.p, reflected);
+        let direction = reflected + (self.fuzz * random_uint_vector());
+        let scatter_ray = Ray::with_time(hit_record.p, direction, ray.time);
         if scatter_ray.direction.dot(hit_record.normal) > 0.0 {
             return Some(ScatterRecord {
                 attenuation: self.albedo,
                 scatter_ray,
+                pdf: self.fuzz_pdf(reflected, direction),
+                is_specular: false,
             });
         }
         None
     }
+
+    /// Directions that would dip below the surface are absorbed, so the lobe is the fuzz
+    /// density scaled by the albedo wherever it stays above the surface.
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> Color {
+        if scattered.dot(hit_record.normal) <= 0.0 {
+            return Color::zero();
+        }
+        self.albedo * self.pdf(ray, hit_record, scattered)
+    }
+
+    fn pdf(&self, ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> f64 {
+        if self.fuzz <= 0.0 {
+            return 0.0;
+        }
+        let reflected = reflect(ray.direction, hit_record.normal).unit();
+        self.fuzz_pdf(reflected, scattered)
+    }
+
+    fn specular_lobes(&self, ray: &Ray, hit_record: &HitRecord) -> Vec<ScatterRecord> {
+        if self.fuzz > 0.0 {
+            return Vec::new();
+        }
+        let reflected = reflect(ray.direction, hit_record.normal).unit();
+        let scatter_ray = Ray::with_time(hit_record.p, reflected, ray.time);
+        vec![ScatterRecord::specular(self.albedo, scatter_ray)]
+    }
+
+    fn albedo(&self, _hit_record: &HitRecord) -> Color {
+        self.albedo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzz_pdf_is_normalized() {
+        // Integrate over the cone the lobe covers, substituting cos = cos_max + s^2 to
+        // tame the 1/sqrt singularity at its rim.
+        let metal = Metal::new(Color::ones(), 0.4);
+        let reflected = Vec3::new(0.0, 0.0, 1.0);
+        let cos_max = (1.0 - 0.4 * 0.4_f64).sqrt();
+        let s_max = (1.0 - cos_max).sqrt();
+        let n = 100_000;
+        let integral: f64 = (0..n)
+            .map(|i| {
+                let s = (i as f64 + 0.5) / n as f64 * s_max;
+                let cos_theta = cos_max + s * s;
+                let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+                let direction = Vec3::new(sin_theta, 0.0, cos_theta);
+                metal.fuzz_pdf(reflected, direction) * 2.0 * PI * 2.0 * s * s_max / n as f64
+            })
+            .sum();
+        assert!((integral - 1.0).abs() < 1e-4, "{integral}");
+    }
 }
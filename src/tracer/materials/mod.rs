@@ -1,7 +1,11 @@
 pub mod dielectric;
+pub mod diffuse_light;
 pub mod lambertian;
 pub mod metal;
+pub mod volumetric;
 
 pub use dielectric::*;
+pub use diffuse_light::*;
 pub use lambertian::*;
 pub use metal::*;
+pub use volumetric::*;
@@ -0,0 +1,49 @@
+use crate::{
+    next_material_id, Color, HitRecord, Material, PhaseFunction, Ray, ScatterRecord, Vec3,
+};
+
+/// Material for scattering events inside a participating medium: attenuates by the
+/// single-scattering `albedo` and picks the new direction from a phase function.
+pub struct Volumetric {
+    pub albedo: Color,
+    pub phase_function: Box<dyn PhaseFunction>,
+    id: usize,
+}
+
+impl Volumetric {
+    pub fn new(albedo: Color, phase_function: impl PhaseFunction + 'static) -> Self {
+        Self {
+            albedo,
+            phase_function: Box::new(phase_function),
+            id: next_material_id(),
+        }
+    }
+}
+
+impl Material for Volumetric {
+    fn material_id(&self) -> usize {
+        self.id
+    }
+
+    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let direction = self.phase_function.sample(ray.direction);
+        Some(ScatterRecord {
+            attenuation: self.albedo,
+            scatter_ray: Ray::with_time(hit_record.p, direction, ray.time),
+            pdf: self.phase_function.p(ray.direction, direction),
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> Color {
+        self.albedo * self.pdf(ray, hit_record, scattered)
+    }
+
+    fn pdf(&self, ray: &Ray, _hit_record: &HitRecord, scattered: Vec3) -> f64 {
+        self.phase_function.p(ray.direction, scattered)
+    }
+
+    fn albedo(&self, _hit_record: &HitRecord) -> Color {
+        self.albedo
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{Color, RefractiveIndex};
+
+/// A dielectric medium that a path is currently inside of.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MediumEntry {
+    pub id: usize,     // Identifies the material that owns the medium
+    pub priority: u32, // Higher priorities win where media overlap
+    pub refraction_index: RefractiveIndex,
+    pub absorption: Color, // Absorption coefficient per unit distance
+}
+
+/// The set of nested media a path has entered, carried along in the path state.
+///
+/// Overlapping volumes are resolved by priority: the highest-priority medium (the most
+/// recently entered one on ties) is the one the path is actually in. Boundaries of
+/// lower-priority media inside it are false interfaces and are passed straight through,
+/// which lets scenes model a liquid in a glass by simply letting the two overlap.
+#[derive(Clone, Debug, Default)]
+pub struct MediumStack {
+    entries: Vec<MediumEntry>,
+}
+
+impl MediumStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The medium the path is currently travelling through, if any.
+    pub fn top(&self) -> Option<&MediumEntry> {
+        // max_by_key returns the last of equal elements, i.e. the latest entered.
+        self.entries.iter().max_by_key(|entry| entry.priority)
+    }
+
+    /// Index of refraction of the current medium at `wavelength`; 1.0 outside every
+    /// medium.
+    pub fn refraction_index(&self, wavelength: Option<f64>) -> f64 {
+        self.top()
+            .map_or(1.0, |entry| entry.refraction_index.at(wavelength))
+    }
+
+    /// Whether any medium on the stack has a wavelength-dependent index.
+    pub fn is_dispersive(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.refraction_index.is_dispersive())
+    }
+
+    /// Fraction of light surviving `distance` units through the current medium. The
+    /// distance may be infinite, for light arriving from the environment.
+    pub fn transmittance(&self, distance: f64) -> Color {
+        let Some(entry) = self.top() else {
+            return Color::ones();
+        };
+        // Clear channels pass everything, even over an infinite distance.
+        let channel = |absorption: f64| {
+            if absorption == 0.0 {
+                1.0
+            } else {
+                (-absorption * distance).exp()
+            }
+        };
+        Color::new(
+            channel(entry.absorption.x),
+            channel(entry.absorption.y),
+            channel(entry.absorption.z),
+        )
+    }
+
+    pub fn contains(&self, id: usize) -> bool {
+        self.entries.iter().any(|entry| entry.id == id)
+    }
+
+    pub fn push(&mut self, entry: MediumEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Removes the most recently entered medium with the given id.
+    pub fn remove(&mut self, id: usize) -> Option<MediumEntry> {
+        let index = self.entries.iter().rposition(|entry| entry.id == id)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Index of refraction the path would be in after leaving the medium `id`.
+    pub fn refraction_index_without(&self, id: usize, wavelength: Option<f64>) -> f64 {
+        let mut rest = self.clone();
+        rest.remove(id);
+        rest.refraction_index(wavelength)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: usize, priority: u32, refraction_index: f64) -> MediumEntry {
+        MediumEntry {
+            id,
+            priority,
+            refraction_index: RefractiveIndex::Constant(refraction_index),
+            absorption: Color::zero(),
+        }
+    }
+
+    #[test]
+    fn test_priority_resolves_overlap() {
+        let mut media = MediumStack::new();
+        assert_eq!(media.refraction_index(None), 1.0);
+
+        media.push(entry(1, 2, 1.5)); // glass
+        media.push(entry(2, 1, 1.33)); // water overlapping the glass wall
+        assert_eq!(media.top().unwrap().id, 1);
+        assert_eq!(media.refraction_index_without(1, None), 1.33);
+
+        media.remove(1);
+        assert_eq!(media.refraction_index(None), 1.33);
+        media.remove(2);
+        assert!(media.top().is_none());
+    }
+
+    #[test]
+    fn test_equal_priority_prefers_latest() {
+        let mut media = MediumStack::new();
+        media.push(entry(1, 0, 1.33));
+        media.push(entry(2, 0, 1.31));
+        assert_eq!(media.top().unwrap().id, 2);
+    }
+
+    #[test]
+    fn test_transmittance_over_infinite_distance() {
+        let mut media = MediumStack::new();
+        assert_eq!(media.transmittance(f64::INFINITY), Color::ones());
+
+        let mut tinted = entry(1, 0, 1.5);
+        tinted.absorption = Color::new(0.5, 0.0, 2.0);
+        media.push(tinted);
+        assert_eq!(
+            media.transmittance(f64::INFINITY),
+            Color::new(0.0, 1.0, 0.0)
+        );
+        assert_eq!(media.transmittance(1.0).y, 1.0);
+        assert!((media.transmittance(1.0).x - (-0.5f64).exp()).abs() < 1e-12);
+    }
+}
@@ -1,23 +1,63 @@
+pub mod aabb;
+pub mod aovs;
 pub mod camera;
+pub mod delta_light;
+pub mod density_grid;
+pub mod distribution;
+pub mod environment;
+pub mod environments;
+pub mod film;
 pub mod hit_record;
 pub mod hittable;
+pub mod integrator;
+pub mod integrators;
 pub mod interval;
 pub mod material;
 pub mod materials;
+pub mod medium_stack;
 pub mod objects;
+pub mod pdf;
+pub mod pdfs;
+pub mod perlin;
+pub mod phase_function;
+pub mod phase_functions;
 pub mod ray;
+pub mod rgb_spectrum;
+pub mod sampled_spectrum;
 pub mod scatter_record;
+pub mod scene;
+pub mod spectrum;
 pub mod utils;
 pub mod vec3;
 
+pub use aabb::*;
+pub use aovs::*;
 pub use camera::*;
+pub use delta_light::*;
+pub use density_grid::*;
+pub use distribution::*;
+pub use environment::*;
+pub use environments::*;
+pub use film::*;
 pub use hit_record::*;
 pub use hittable::*;
+pub use integrator::*;
+pub use integrators::*;
 pub use interval::*;
 pub use material::*;
 pub use materials::*;
+pub use medium_stack::*;
 pub use objects::*;
+pub use pdf::*;
+pub use pdfs::*;
+pub use perlin::*;
+pub use phase_function::*;
+pub use phase_functions::*;
 pub use ray::*;
+pub use rgb_spectrum::*;
+pub use sampled_spectrum::*;
 pub use scatter_record::*;
+pub use scene::*;
+pub use spectrum::*;
 pub use utils::*;
 pub use vec3::*;
@@ -0,0 +1,119 @@
+use crate::{Aabb, HitRecord, Hittable, Interval, Material, Ray, Vec3};
+
+/// A solid box aligned with the coordinate axes, spanning the corners `min` and `max`.
+pub struct AxisBox<M: Material> {
+    pub min: Vec3,
+    pub max: Vec3,
+    pub material: M,
+}
+
+impl<M: Material> AxisBox<M> {
+    pub fn new(a: Vec3, b: Vec3, material: M) -> Self {
+        Self {
+            min: Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+            material,
+        }
+    }
+}
+
+impl<M: Material> Hittable for AxisBox<M> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        // Slab test, remembering which axis bounds the entry and exit points.
+        let mut t_near = f64::NEG_INFINITY;
+        let mut t_far = f64::INFINITY;
+        let mut near_axis = 0;
+        let mut far_axis = 0;
+        for axis in 0..3 {
+            let adinv = 1.0 / ray.direction[axis];
+            let t0 = (self.min[axis] - ray.origin[axis]) * adinv;
+            let t1 = (self.max[axis] - ray.origin[axis]) * adinv;
+            let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+            if t0 > t_near {
+                t_near = t0;
+                near_axis = axis;
+            }
+            if t1 < t_far {
+                t_far = t1;
+                far_axis = axis;
+            }
+        }
+        if t_near > t_far {
+            return None;
+        }
+
+        // Take the entry point, or the exit point if the ray starts inside.
+        let (t, axis) = if ray_t.surrounds(t_near) {
+            (t_near, near_axis)
+        } else if ray_t.surrounds(t_far) {
+            (t_far, far_axis)
+        } else {
+            return None;
+        };
+
+        let p = ray.at(t);
+        let center = (self.min + self.max) / 2.0;
+        let mut outward_normal = Vec3::zero();
+        outward_normal[axis] = if p[axis] > center[axis] { 1.0 } else { -1.0 };
+
+        // UVs are the face coordinates of the two remaining axes, normalized to [0,1].
+        let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+        let u = (p[a] - self.min[a]) / (self.max[a] - self.min[a]);
+        let v = (p[b] - self.min[b]) / (self.max[b] - self.min[b]);
+
+        let mut hr = HitRecord {
+            normal: outward_normal,
+            p,
+            t,
+            u,
+            v,
+            front_face: false,
+            material: &self.material,
+        };
+        hr.set_face_normal(ray, outward_normal);
+
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(self.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lambertian;
+
+    fn unit_box() -> AxisBox<Lambertian> {
+        AxisBox::new(
+            Vec3::new(1.0, 1.0, 1.0),
+            Vec3::new(-1.0, -1.0, -1.0),
+            Lambertian::new(Vec3::ones()),
+        )
+    }
+
+    #[test]
+    fn test_hit_from_outside() {
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let bbox = unit_box();
+        let hr = bbox.hit(&ray, Interval::new(0.0, f64::INFINITY)).unwrap();
+        assert_eq!(hr.t, 4.0);
+        assert!(hr.front_face);
+        assert_eq!(hr.normal, Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!((hr.u, hr.v), (0.5, 0.5));
+
+        let miss = Ray::new(Vec3::new(0.0, 2.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(bbox.hit(&miss, Interval::new(0.0, f64::INFINITY)).is_none());
+    }
+
+    #[test]
+    fn test_hit_from_inside() {
+        let ray = Ray::new(Vec3::zero(), Vec3::new(1.0, 0.0, 0.0));
+        let bbox = unit_box();
+        let hr = bbox.hit(&ray, Interval::new(0.001, f64::INFINITY)).unwrap();
+        assert_eq!(hr.t, 1.0);
+        assert!(!hr.front_face);
+        assert_eq!(hr.normal, Vec3::new(-1.0, 0.0, 0.0));
+    }
+}
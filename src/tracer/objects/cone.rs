@@ -0,0 +1,157 @@
+use std::f64::consts::PI;
+
+use crate::{Aabb, HitRecord, Hittable, Interval, Material, Onb, Ray, Vec3};
+
+/// A cone with a capped circular base at `base` and its apex at `base + axis`.
+pub struct Cone<M: Material> {
+    pub base: Vec3,
+    pub axis: Vec3,
+    pub radius: f64,
+    pub material: M,
+    height: f64,
+    onb: Onb,
+}
+
+impl<M: Material> Cone<M> {
+    pub fn new(base: Vec3, axis: Vec3, radius: f64, material: M) -> Self {
+        Self {
+            base,
+            axis,
+            radius: radius.max(0.0),
+            material,
+            height: axis.length(),
+            onb: Onb::new(axis),
+        }
+    }
+}
+
+impl<M: Material> Hittable for Cone<M> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let o = self.onb.to_local(ray.origin - self.base);
+        let d = self.onb.to_local(ray.direction);
+        let k = self.radius / self.height;
+        let k2 = k * k;
+
+        let mut best: Option<(f64, Vec3)> = None;
+        let mut consider = |t: f64, normal: Vec3| {
+            if ray_t.surrounds(t) && best.is_none_or(|(bt, _)| t < bt) {
+                best = Some((t, normal));
+            }
+        };
+
+        // Side: x^2 + y^2 = k^2 (height - z)^2 with 0 <= z <= height.
+        let hz = self.height - o.z;
+        let a = d.x * d.x + d.y * d.y - k2 * d.z * d.z;
+        let half_b = o.x * d.x + o.y * d.y + k2 * hz * d.z;
+        let c = o.x * o.x + o.y * o.y - k2 * hz * hz;
+        let roots = if a.abs() > 1e-12 {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant >= 0.0 {
+                let sqrtd = discriminant.sqrt();
+                vec![(-half_b - sqrtd) / a, (-half_b + sqrtd) / a]
+            } else {
+                vec![]
+            }
+        } else if half_b.abs() > 1e-12 {
+            // Ray parallel to the slant: the quadratic degenerates to a line.
+            vec![-c / (2.0 * half_b)]
+        } else {
+            vec![]
+        };
+        for t in roots {
+            let p = o + t * d;
+            if (0.0..=self.height).contains(&p.z) {
+                let normal = Vec3::new(p.x, p.y, k2 * (self.height - p.z));
+                if !normal.near_zero() {
+                    consider(t, normal.unit());
+                }
+            }
+        }
+
+        // Base cap at z = 0.
+        if d.z.abs() > 1e-12 {
+            let t = -o.z / d.z;
+            let p = o + t * d;
+            if p.x * p.x + p.y * p.y <= self.radius * self.radius {
+                consider(t, Vec3::new(0.0, 0.0, -1.0));
+            }
+        }
+
+        let (t, local_normal) = best?;
+        let lp = o + t * d;
+        let u = (lp.y.atan2(lp.x) + PI) / (2.0 * PI);
+        let v = if local_normal.z == -1.0 {
+            (lp.x * lp.x + lp.y * lp.y).sqrt() / self.radius
+        } else {
+            lp.z / self.height
+        };
+
+        let outward_normal = self.onb.transform(local_normal);
+        let mut hr = HitRecord {
+            normal: outward_normal,
+            p: ray.at(t),
+            t,
+            u,
+            v,
+            front_face: false,
+            material: &self.material,
+        };
+        hr.set_face_normal(ray, outward_normal);
+
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let apex = self.base + self.axis;
+        Aabb::surrounding(
+            &Aabb::from_disk(self.base, self.onb.w, self.radius),
+            &Aabb::from_points(apex, apex),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lambertian;
+
+    fn test_cone() -> Cone<Lambertian> {
+        Cone::new(
+            Vec3::zero(),
+            Vec3::new(0.0, 2.0, 0.0),
+            1.0,
+            Lambertian::new(Vec3::ones()),
+        )
+    }
+
+    #[test]
+    fn test_side() {
+        let cone = test_cone();
+        // Halfway up, the radius is 0.5.
+        let ray = Ray::new(Vec3::new(-5.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let hr = cone.hit(&ray, Interval::new(0.001, f64::INFINITY)).unwrap();
+        assert!((hr.t - 4.5).abs() < 1e-9);
+        assert!((hr.normal - Vec3::new(-1.0, 0.5, 0.0).unit()).length() < 1e-9);
+        assert!(hr.front_face);
+    }
+
+    #[test]
+    fn test_base_cap() {
+        let cone = test_cone();
+        let ray = Ray::new(Vec3::new(0.2, -3.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let hr = cone.hit(&ray, Interval::new(0.001, f64::INFINITY)).unwrap();
+        assert!((hr.t - 3.0).abs() < 1e-9);
+        assert!((hr.normal - Vec3::new(0.0, -1.0, 0.0)).length() < 1e-9);
+        assert!((hr.v - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_miss_near_apex() {
+        let cone = test_cone();
+        // Near the apex the cone is only 0.05 wide.
+        let ray = Ray::new(Vec3::new(-5.0, 1.9, 0.2), Vec3::new(1.0, 0.0, 0.0));
+        assert!(cone
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .is_none());
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    random_f64, Aabb, Color, HitRecord, Hittable, Interval, IsotropicPhase, PhaseFunction, Ray,
+    Vec3, Volumetric,
+};
+
+/// A volume of uniform density filling a closed `boundary`, such as smoke or fog.
+///
+/// Rays passing through scatter after an exponentially distributed distance, so thin
+/// media let most light through and dense ones behave almost like a solid. Scattering
+/// is isotropic unless another phase function is set with `with_phase_function`.
+pub struct ConstantMedium {
+    pub boundary: Box<dyn Hittable>,
+    pub material: Volumetric,
+    neg_inv_density: f64,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Box<dyn Hittable>, density: f64, albedo: Color) -> Self {
+        Self {
+            boundary,
+            material: Volumetric::new(albedo, IsotropicPhase),
+            neg_inv_density: -1.0 / density,
+        }
+    }
+
+    pub fn with_phase_function(mut self, phase_function: impl PhaseFunction + 'static) -> Self {
+        self.material.phase_function = Box::new(phase_function);
+        self
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        // Find where the ray enters and leaves the boundary, even if it starts inside.
+        let enter = self.boundary.hit(ray, Interval::universe())?;
+        let exit = self
+            .boundary
+            .hit(ray, Interval::new(enter.t + 0.0001, f64::INFINITY))?;
+
+        let t_min = enter.t.max(ray_t.min).max(0.0);
+        let t_max = exit.t.min(ray_t.max);
+        if t_min >= t_max {
+            return None;
+        }
+
+        let ray_length = ray.direction.length();
+        let distance_inside_boundary = (t_max - t_min) * ray_length;
+        let hit_distance = self.neg_inv_density * random_f64().ln();
+        if hit_distance > distance_inside_boundary {
+            return None;
+        }
+
+        let t = t_min + hit_distance / ray_length;
+        Some(HitRecord {
+            normal: Vec3::new(1.0, 0.0, 0.0), // arbitrary
+            p: ray.at(t),
+            t,
+            u: 0.0,
+            v: 0.0,
+            front_face: true, // also arbitrary
+            material: &self.material,
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+
+    fn transmittance(&self, ray: &Ray, ray_t: Interval) -> f64 {
+        let Some(enter) = self.boundary.hit(ray, Interval::universe()) else {
+            return 1.0;
+        };
+        let Some(exit) = self
+            .boundary
+            .hit(ray, Interval::new(enter.t + 0.0001, f64::INFINITY))
+        else {
+            return 1.0;
+        };
+
+        let t_min = enter.t.max(ray_t.min).max(0.0);
+        let t_max = exit.t.min(ray_t.max);
+        if t_min >= t_max {
+            return 1.0;
+        }
+        let distance = (t_max - t_min) * ray.direction.length();
+        // neg_inv_density is -1/density, so this is exp(-density * distance).
+        (distance / self.neg_inv_density).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AxisBox, Lambertian};
+
+    /// A slab `thickness` deep along z and wide enough that rays never leave its sides.
+    fn slab(thickness: f64, density: f64) -> ConstantMedium {
+        let boundary = AxisBox::new(
+            Vec3::new(-5.0, -5.0, 0.0),
+            Vec3::new(5.0, 5.0, thickness),
+            Lambertian::new(Vec3::ones()),
+        );
+        ConstantMedium::new(Box::new(boundary), density, Vec3::ones())
+    }
+
+    #[test]
+    fn test_thin_slab_hit_fraction() {
+        let (thickness, density) = (0.2, 2.0);
+        let medium = slab(thickness, density);
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 0.0, 2.0));
+
+        let n = 20000;
+        let hits = (0..n)
+            .filter(|_| {
+                medium
+                    .hit(&ray, Interval::new(0.001, f64::INFINITY))
+                    .is_some()
+            })
+            .count();
+        let expected = 1.0 - (-density * thickness).exp();
+        let fraction = hits as f64 / n as f64;
+        assert!(
+            (fraction - expected).abs() < 0.02,
+            "{fraction} vs {expected}"
+        );
+    }
+
+    #[test]
+    fn test_transmittance() {
+        let medium = slab(0.2, 2.0);
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 0.0, 2.0));
+        let t = medium.transmittance(&ray, Interval::new(0.001, f64::INFINITY));
+        assert!((t - (-0.4f64).exp()).abs() < 1e-9);
+
+        // Stopping halfway through the slab only attenuates over the first half.
+        let t = medium.transmittance(&ray, Interval::new(0.001, 0.55));
+        assert!((t - (-0.2f64).exp()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,157 @@
+use std::f64::consts::PI;
+
+use crate::{Aabb, HitRecord, Hittable, Interval, Material, Onb, Ray, Vec3};
+
+/// A closed cylinder from `base` to `base + axis` with end caps.
+pub struct Cylinder<M: Material> {
+    pub base: Vec3,
+    pub axis: Vec3,
+    pub radius: f64,
+    pub material: M,
+    height: f64,
+    onb: Onb,
+}
+
+impl<M: Material> Cylinder<M> {
+    pub fn new(base: Vec3, axis: Vec3, radius: f64, material: M) -> Self {
+        Self {
+            base,
+            axis,
+            radius: radius.max(0.0),
+            material,
+            height: axis.length(),
+            onb: Onb::new(axis),
+        }
+    }
+}
+
+impl<M: Material> Hittable for Cylinder<M> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let o = self.onb.to_local(ray.origin - self.base);
+        let d = self.onb.to_local(ray.direction);
+        let r2 = self.radius * self.radius;
+
+        // Candidate hits as (t, local outward normal).
+        let mut best: Option<(f64, Vec3)> = None;
+        let mut consider = |t: f64, normal: Vec3| {
+            if ray_t.surrounds(t) && best.is_none_or(|(bt, _)| t < bt) {
+                best = Some((t, normal));
+            }
+        };
+
+        // Side: x^2 + y^2 = r^2 with 0 <= z <= height.
+        let a = d.x * d.x + d.y * d.y;
+        if a > 1e-12 {
+            let h = o.x * d.x + o.y * d.y;
+            let c = o.x * o.x + o.y * o.y - r2;
+            let discriminant = h * h - a * c;
+            if discriminant >= 0.0 {
+                let sqrtd = discriminant.sqrt();
+                for t in [(-h - sqrtd) / a, (-h + sqrtd) / a] {
+                    let z = o.z + t * d.z;
+                    if (0.0..=self.height).contains(&z) {
+                        let p = o + t * d;
+                        consider(t, Vec3::new(p.x, p.y, 0.0) / self.radius);
+                    }
+                }
+            }
+        }
+
+        // Caps at z = 0 and z = height.
+        if d.z.abs() > 1e-12 {
+            for (z, nz) in [(0.0, -1.0), (self.height, 1.0)] {
+                let t = (z - o.z) / d.z;
+                let p = o + t * d;
+                if p.x * p.x + p.y * p.y <= r2 {
+                    consider(t, Vec3::new(0.0, 0.0, nz));
+                }
+            }
+        }
+
+        let (t, local_normal) = best?;
+        let lp = o + t * d;
+        let u = (lp.y.atan2(lp.x) + PI) / (2.0 * PI);
+        let v = if local_normal.z == 0.0 {
+            lp.z / self.height
+        } else {
+            // Caps map radially, like a disk.
+            (lp.x * lp.x + lp.y * lp.y).sqrt() / self.radius
+        };
+
+        let outward_normal = self.onb.transform(local_normal);
+        let mut hr = HitRecord {
+            normal: outward_normal,
+            p: ray.at(t),
+            t,
+            u,
+            v,
+            front_face: false,
+            material: &self.material,
+        };
+        hr.set_face_normal(ray, outward_normal);
+
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let w = self.onb.w;
+        Aabb::surrounding(
+            &Aabb::from_disk(self.base, w, self.radius),
+            &Aabb::from_disk(self.base + self.axis, w, self.radius),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lambertian;
+
+    fn test_cylinder() -> Cylinder<Lambertian> {
+        Cylinder::new(
+            Vec3::zero(),
+            Vec3::new(0.0, 2.0, 0.0),
+            1.0,
+            Lambertian::new(Vec3::ones()),
+        )
+    }
+
+    fn hit(ray: Ray) -> Option<(f64, Vec3, bool, f64)> {
+        let cylinder = test_cylinder();
+        let hr = cylinder.hit(&ray, Interval::new(0.001, f64::INFINITY))?;
+        Some((hr.t, hr.normal, hr.front_face, hr.v))
+    }
+
+    #[test]
+    fn test_side() {
+        let ray = Ray::new(Vec3::new(-5.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let (t, normal, front_face, v) = hit(ray).unwrap();
+        assert!((t - 4.0).abs() < 1e-9);
+        assert!((normal - Vec3::new(-1.0, 0.0, 0.0)).length() < 1e-9);
+        assert!(front_face);
+        assert!((v - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_caps() {
+        let top = Ray::new(Vec3::new(0.5, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let (t, normal, front_face, v) = hit(top).unwrap();
+        assert!((t - 3.0).abs() < 1e-9);
+        assert!((normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-9);
+        assert!(front_face);
+        assert!((v - 0.5).abs() < 1e-9);
+
+        // From inside, the bottom cap faces away from the ray.
+        let inside = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let (t, normal, front_face, _) = hit(inside).unwrap();
+        assert!((t - 1.0).abs() < 1e-9);
+        assert!((normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-9);
+        assert!(!front_face);
+    }
+
+    #[test]
+    fn test_miss_above() {
+        let ray = Ray::new(Vec3::new(-5.0, 3.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(hit(ray).is_none());
+    }
+}
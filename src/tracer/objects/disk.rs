@@ -0,0 +1,173 @@
+use std::f64::consts::PI;
+
+use crate::{random_f64, Aabb, HitRecord, Hittable, Interval, Material, Onb, Point3, Ray, Vec3};
+
+/// A flat circular disk facing along `normal`.
+pub struct Disk<M: Material> {
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub radius: f64,
+    pub material: M,
+    onb: Onb,
+}
+
+impl<M: Material> Disk<M> {
+    pub fn new(center: Vec3, normal: Vec3, radius: f64, material: M) -> Self {
+        let normal = normal.unit();
+        Self {
+            center,
+            normal,
+            radius: radius.max(0.0),
+            material,
+            onb: Onb::new(normal),
+        }
+    }
+
+    /// Texture coordinates of the point at `offset` from the center: u runs around the
+    /// rim, v from the center outwards.
+    fn uv(&self, offset: Vec3) -> (f64, f64) {
+        let phi = offset.dot(self.onb.v).atan2(offset.dot(self.onb.u));
+        ((phi + PI) / (2.0 * PI), offset.length() / self.radius)
+    }
+}
+
+impl<M: Material> Hittable for Disk<M> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let denom = self.normal.dot(ray.direction);
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = self.normal.dot(self.center - ray.origin) / denom;
+        if !ray_t.surrounds(t) {
+            return None;
+        }
+
+        let p = ray.at(t);
+        let offset = p - self.center;
+        let dist_squared = offset.length_squard();
+        if dist_squared > self.radius * self.radius {
+            return None;
+        }
+
+        let (u, v) = self.uv(offset);
+
+        let mut hr = HitRecord {
+            normal: self.normal,
+            p,
+            t,
+            u,
+            v,
+            front_face: false,
+            material: &self.material,
+        };
+        hr.set_face_normal(ray, self.normal);
+
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_disk(self.center, self.normal, self.radius)
+    }
+
+    fn is_emissive(&self) -> bool {
+        self.material.is_emissive()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let ray = Ray::new(origin, direction);
+        let Some(hr) = self.hit(&ray, Interval::new(0.001, f64::INFINITY)) else {
+            return 0.0;
+        };
+
+        let distance_squared = hr.t * hr.t * direction.length_squard();
+        let cosine = (direction.dot(hr.normal) / direction.length()).abs();
+        distance_squared / (cosine * self.area())
+    }
+
+    fn random(&self, origin: Point3) -> Vec3 {
+        // The square root keeps points uniform over the area rather than the radius.
+        let r = self.radius * random_f64().sqrt();
+        let phi = 2.0 * PI * random_f64();
+        let p = self.center + r * (phi.cos() * self.onb.u + phi.sin() * self.onb.v);
+        p - origin
+    }
+
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    fn sample_surface(&self) -> Option<HitRecord<'_>> {
+        let r = self.radius * random_f64().sqrt();
+        let phi = 2.0 * PI * random_f64();
+        let offset = r * (phi.cos() * self.onb.u + phi.sin() * self.onb.v);
+        let (u, v) = self.uv(offset);
+        Some(HitRecord {
+            normal: self.normal,
+            p: self.center + offset,
+            t: 0.0,
+            u,
+            v,
+            front_face: true,
+            material: &self.material,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lambertian;
+
+    fn test_disk() -> Disk<Lambertian> {
+        Disk::new(
+            Vec3::zero(),
+            Vec3::new(0.0, 2.0, 0.0),
+            1.0,
+            Lambertian::new(Vec3::ones()),
+        )
+    }
+
+    #[test]
+    fn test_hit() {
+        let disk = test_disk();
+        let ray = Ray::new(Vec3::new(0.5, 3.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let hr = disk.hit(&ray, Interval::new(0.0, f64::INFINITY)).unwrap();
+        assert_eq!(hr.t, 3.0);
+        assert!(hr.front_face);
+        assert_eq!(hr.normal, Vec3::new(0.0, 1.0, 0.0));
+        assert!((hr.v - 0.5).abs() < 1e-12);
+        assert!((0.0..=1.0).contains(&hr.u));
+    }
+
+    #[test]
+    fn test_miss() {
+        let disk = test_disk();
+        let outside = Ray::new(Vec3::new(0.8, 3.0, 0.8), Vec3::new(0.0, -1.0, 0.0));
+        assert!(disk
+            .hit(&outside, Interval::new(0.0, f64::INFINITY))
+            .is_none());
+        let parallel = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(disk
+            .hit(&parallel, Interval::new(0.0, f64::INFINITY))
+            .is_none());
+    }
+
+    #[test]
+    fn test_sampled_uv_matches_hit() {
+        let disk = Disk::new(
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            2.0,
+            Lambertian::new(Vec3::ones()),
+        );
+        for _ in 0..100 {
+            let sample = disk.sample_surface().unwrap();
+            let ray = Ray::new(sample.p + sample.normal, -sample.normal);
+            let hr = disk.hit(&ray, Interval::new(0.0, f64::INFINITY)).unwrap();
+            assert!((hr.p - sample.p).length() < 1e-9);
+            assert!((hr.u - sample.u).abs() < 1e-9, "{} vs {}", hr.u, sample.u);
+            assert!((hr.v - sample.v).abs() < 1e-9);
+        }
+    }
+}
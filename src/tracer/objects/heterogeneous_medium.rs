@@ -0,0 +1,264 @@
+use crate::{
+    blackbody_color, next_material_id, random_f64, Aabb, Color, DensityGrid, HitRecord, Hittable,
+    Interval, IsotropicPhase, Material, PhaseFunction, Ray, ScatterRecord, Vec3,
+};
+
+/// Light given off by a heterogeneous medium.
+pub enum VolumeEmission {
+    /// Radiance `color * grid(p)`.
+    Grid { grid: DensityGrid, color: Color },
+    /// Black-body radiation from a temperature grid in Kelvin. Brightness grows with
+    /// the fourth power of temperature, relative to 1000K, times `scale`.
+    Temperature { grid: DensityGrid, scale: f64 },
+}
+
+impl VolumeEmission {
+    fn radiance(&self, p: Vec3) -> Color {
+        match self {
+            VolumeEmission::Grid { grid, color } => *color * grid.value(p),
+            VolumeEmission::Temperature { grid, scale } => {
+                let temperature = grid.value(p);
+                blackbody_color(temperature) * (*scale * (temperature / 1000.0).powi(4))
+            }
+        }
+    }
+}
+
+/// A medium whose density varies through space according to a voxel grid, such as
+/// clouds or fire.
+///
+/// Scattering events are found with delta tracking against the grid's maximum density,
+/// and shadow queries use ratio tracking, so both are unbiased for any density field.
+pub struct HeterogeneousMedium {
+    pub density: DensityGrid,
+    pub density_scale: f64, // Extinction coefficient per unit of grid density
+    material: VolumeMaterial,
+}
+
+impl HeterogeneousMedium {
+    pub fn new(density: DensityGrid, density_scale: f64, albedo: Color) -> Self {
+        Self {
+            density,
+            density_scale,
+            material: VolumeMaterial {
+                albedo,
+                phase_function: Box::new(IsotropicPhase),
+                emission: None,
+                id: next_material_id(),
+            },
+        }
+    }
+
+    pub fn with_phase_function(mut self, phase_function: impl PhaseFunction + 'static) -> Self {
+        self.material.phase_function = Box::new(phase_function);
+        self
+    }
+
+    pub fn with_emission(mut self, emission: VolumeEmission) -> Self {
+        self.material.emission = Some(emission);
+        self
+    }
+
+    fn majorant(&self) -> f64 {
+        self.density.max_value() * self.density_scale
+    }
+}
+
+impl Hittable for HeterogeneousMedium {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let majorant = self.majorant();
+        if majorant <= 0.0 {
+            return None;
+        }
+        let segment = self.density.bounds.hit(ray, ray_t)?;
+        let ray_length = ray.direction.length();
+
+        // Delta tracking: take exponential steps against the majorant and accept each
+        // tentative collision with probability density / majorant.
+        let mut t = segment.min;
+        loop {
+            t -= (1.0 - random_f64()).ln() / (majorant * ray_length);
+            if t >= segment.max {
+                return None;
+            }
+            let p = ray.at(t);
+            if random_f64() * majorant < self.density.value(p) * self.density_scale {
+                return Some(HitRecord {
+                    normal: Vec3::new(1.0, 0.0, 0.0), // arbitrary
+                    p,
+                    t,
+                    u: 0.0,
+                    v: 0.0,
+                    front_face: true, // also arbitrary
+                    material: &self.material,
+                });
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.density.bounds
+    }
+
+    fn transmittance(&self, ray: &Ray, ray_t: Interval) -> f64 {
+        let majorant = self.majorant();
+        let Some(segment) = self.density.bounds.hit(ray, ray_t) else {
+            return 1.0;
+        };
+        if majorant <= 0.0 {
+            return 1.0;
+        }
+        let ray_length = ray.direction.length();
+
+        // Ratio tracking: weight by the null-collision probability at each tentative step.
+        let mut transmittance = 1.0;
+        let mut t = segment.min;
+        loop {
+            t -= (1.0 - random_f64()).ln() / (majorant * ray_length);
+            if t >= segment.max {
+                return transmittance;
+            }
+            let density = self.density.value(ray.at(t)) * self.density_scale;
+            transmittance *= 1.0 - density / majorant;
+            if transmittance < 1e-4 {
+                return 0.0;
+            }
+        }
+    }
+}
+
+/// Material attached to collisions inside a heterogeneous medium.
+///
+/// A collision either scatters (with probability `albedo`) or absorbs; rather than
+/// choosing, scattering is weighted by the albedo and emission by the absorbed fraction.
+struct VolumeMaterial {
+    albedo: Color,
+    phase_function: Box<dyn PhaseFunction>,
+    emission: Option<VolumeEmission>,
+    id: usize,
+}
+
+impl Material for VolumeMaterial {
+    fn material_id(&self) -> usize {
+        self.id
+    }
+
+    fn scatter(&self, ray: &Ray, hit_record: &HitRecord) -> Option<ScatterRecord> {
+        let direction = self.phase_function.sample(ray.direction);
+        Some(ScatterRecord {
+            attenuation: self.albedo,
+            scatter_ray: Ray::with_time(hit_record.p, direction, ray.time),
+            pdf: self.phase_function.p(ray.direction, direction),
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, hit_record: &HitRecord, scattered: Vec3) -> Color {
+        self.albedo * self.pdf(ray, hit_record, scattered)
+    }
+
+    fn pdf(&self, ray: &Ray, _hit_record: &HitRecord, scattered: Vec3) -> f64 {
+        self.phase_function.p(ray.direction, scattered)
+    }
+
+    fn albedo(&self, _hit_record: &HitRecord) -> Color {
+        self.albedo
+    }
+
+    fn emitted(&self, _ray: &Ray, hit_record: &HitRecord) -> Color {
+        match &self.emission {
+            Some(emission) => (Color::ones() - self.albedo) * emission.radiance(hit_record.p),
+            None => Color::zero(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A slab `thickness` deep along z and wide enough that rays never leave its sides,
+    /// with grid density `near` at the front and `far` at the back.
+    fn slab(thickness: f64, near: f64, far: f64) -> DensityGrid {
+        let bounds = Aabb::from_points(Vec3::new(-5.0, -5.0, 0.0), Vec3::new(5.0, 5.0, thickness));
+        DensityGrid::new([1, 1, 2], bounds, vec![near, far])
+    }
+
+    fn ray() -> Ray {
+        Ray::new(Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 0.0, 2.0))
+    }
+
+    #[test]
+    fn test_uniform_hit_fraction() {
+        let (thickness, density_scale) = (0.2, 2.0);
+        let medium =
+            HeterogeneousMedium::new(slab(thickness, 1.0, 1.0), density_scale, Color::ones());
+
+        let n = 20000;
+        let hits = (0..n)
+            .filter(|_| {
+                medium
+                    .hit(&ray(), Interval::new(0.001, f64::INFINITY))
+                    .is_some()
+            })
+            .count();
+        let expected = 1.0 - (-density_scale * thickness).exp();
+        let fraction = hits as f64 / n as f64;
+        assert!(
+            (fraction - expected).abs() < 0.02,
+            "{fraction} vs {expected}"
+        );
+    }
+
+    #[test]
+    fn test_ratio_tracking_transmittance() {
+        // Trilinear interpolation between the two voxels averages to (near + far) / 2
+        // over the depth of the slab.
+        let (thickness, near, far, density_scale) = (0.5, 0.5, 2.0, 1.5);
+        let medium =
+            HeterogeneousMedium::new(slab(thickness, near, far), density_scale, Color::ones());
+
+        let n = 20000;
+        let mean = (0..n)
+            .map(|_| medium.transmittance(&ray(), Interval::new(0.001, f64::INFINITY)))
+            .sum::<f64>()
+            / n as f64;
+        let expected = (-density_scale * thickness * (near + far) / 2.0).exp();
+        assert!((mean - expected).abs() < 0.015, "{mean} vs {expected}");
+    }
+
+    #[test]
+    fn test_empty_grid_never_hits() {
+        let medium = HeterogeneousMedium::new(slab(1.0, 0.0, 0.0), 5.0, Color::ones());
+        for _ in 0..100 {
+            assert!(medium
+                .hit(&ray(), Interval::new(0.001, f64::INFINITY))
+                .is_none());
+        }
+        let t = medium.transmittance(&ray(), Interval::new(0.001, f64::INFINITY));
+        assert_eq!(t, 1.0);
+    }
+
+    #[test]
+    fn test_temperature_emission() {
+        let emitted = |temperature: f64, albedo: f64| {
+            let medium =
+                HeterogeneousMedium::new(slab(1.0, 1.0, 1.0), 1000.0, Color::ones() * albedo)
+                    .with_emission(VolumeEmission::Temperature {
+                        grid: slab(1.0, temperature, temperature),
+                        scale: 1.0,
+                    });
+            let hr = medium
+                .hit(&ray(), Interval::new(0.001, f64::INFINITY))
+                .unwrap();
+            hr.material.emitted(&ray(), &hr)
+        };
+
+        assert_eq!(emitted(0.0, 0.25), Color::zero());
+        // Only the absorbed fraction of collisions emits.
+        let glow = emitted(1000.0, 0.25);
+        assert!((glow - 0.75 * blackbody_color(1000.0)).length() < 1e-9);
+        assert!(glow.length() > 0.0);
+        assert_eq!(emitted(1000.0, 1.0), Color::zero());
+    }
+}
@@ -1,3 +1,25 @@
+pub mod axis_box;
+pub mod cone;
+pub mod constant_medium;
+pub mod cylinder;
+pub mod disk;
+pub mod heterogeneous_medium;
+pub mod moving_sphere;
+pub mod plane;
+pub mod quad;
 pub mod sphere;
+pub mod torus;
+pub mod transform;
 
+pub use axis_box::*;
+pub use cone::*;
+pub use constant_medium::*;
+pub use cylinder::*;
+pub use disk::*;
+pub use heterogeneous_medium::*;
+pub use moving_sphere::*;
+pub use plane::*;
+pub use quad::*;
 pub use sphere::*;
+pub use torus::*;
+pub use transform::*;
@@ -0,0 +1,122 @@
+use crate::{hit_sphere, Aabb, HitRecord, Hittable, Interval, Material, Point3, Ray, Vec3};
+
+/// A sphere whose center follows a piecewise-linear path through `(time, center)`
+/// keyframes. Before the first and after the last keyframe it stays put.
+pub struct MovingSphere<M: Material> {
+    pub keyframes: Vec<(f64, Point3)>,
+    pub radius: f64,
+    pub material: M,
+}
+
+impl<M: Material> MovingSphere<M> {
+    /// Moves linearly from `center0` at time 0 to `center1` at time 1.
+    pub fn new(center0: Point3, center1: Point3, radius: f64, material: M) -> Self {
+        Self::keyframed(vec![(0.0, center0), (1.0, center1)], radius, material)
+    }
+
+    /// Panics if `keyframes` is empty.
+    pub fn keyframed(mut keyframes: Vec<(f64, Point3)>, radius: f64, material: M) -> Self {
+        assert!(!keyframes.is_empty(), "a moving sphere needs a keyframe");
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            keyframes,
+            radius: radius.max(0.0),
+            material,
+        }
+    }
+
+    pub fn center(&self, time: f64) -> Point3 {
+        let keys = &self.keyframes;
+        let next = keys.partition_point(|&(t, _)| t <= time);
+        if next == 0 {
+            return keys[0].1;
+        }
+        if next == keys.len() {
+            return keys[keys.len() - 1].1;
+        }
+        let (t0, c0) = keys[next - 1];
+        let (t1, c1) = keys[next];
+        let a = (time - t0) / (t1 - t0);
+        c0 * (1.0 - a) + c1 * a
+    }
+}
+
+impl<M: Material> Hittable for MovingSphere<M> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        hit_sphere(
+            self.center(ray.time),
+            self.radius,
+            &self.material,
+            ray,
+            ray_t,
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // The path is piecewise linear, so the spheres at the keyframes bound it.
+        let rvec = Vec3::new(self.radius, self.radius, self.radius);
+        self.keyframes.iter().fold(Aabb::empty(), |bbox, &(_, c)| {
+            Aabb::surrounding(&bbox, &Aabb::from_points(c - rvec, c + rvec))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lambertian;
+
+    fn test_sphere() -> MovingSphere<Lambertian> {
+        MovingSphere::keyframed(
+            vec![
+                (1.0, Vec3::new(2.0, 2.0, 0.0)),
+                (0.0, Vec3::zero()),
+                (2.0, Vec3::new(4.0, 0.0, 0.0)),
+            ],
+            0.5,
+            Lambertian::new(Vec3::ones()),
+        )
+    }
+
+    #[test]
+    fn test_center() {
+        let sphere = test_sphere();
+        assert_eq!(sphere.center(-1.0), Vec3::zero());
+        assert_eq!(sphere.center(0.5), Vec3::new(1.0, 1.0, 0.0));
+        assert_eq!(sphere.center(1.5), Vec3::new(3.0, 1.0, 0.0));
+        assert_eq!(sphere.center(3.0), Vec3::new(4.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_hit_at_time() {
+        let sphere = test_sphere();
+        for &time in &[0.25, 1.0, 1.75] {
+            let center = sphere.center(time);
+            let ray = Ray::with_time(
+                center + Vec3::new(0.0, 0.0, 5.0),
+                Vec3::new(0.0, 0.0, -1.0),
+                time,
+            );
+            let hr = sphere
+                .hit(&ray, Interval::new(0.001, f64::INFINITY))
+                .unwrap();
+            assert!((hr.t - 4.5).abs() < 1e-9);
+            assert!((hr.p - (center + Vec3::new(0.0, 0.0, 0.5))).length() < 1e-9);
+            assert!((hr.normal - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-9);
+        }
+
+        // The same ray at another time misses the sphere, which has moved on.
+        let ray = Ray::with_time(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0), 1.0);
+        assert!(sphere
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .is_none());
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bbox = test_sphere().bounding_box();
+        assert_eq!((bbox.x.min, bbox.x.max), (-0.5, 4.5));
+        assert_eq!((bbox.y.min, bbox.y.max), (-0.5, 2.5));
+        assert_eq!((bbox.z.min, bbox.z.max), (-0.5, 0.5));
+    }
+}
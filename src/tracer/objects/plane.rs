@@ -0,0 +1,82 @@
+use crate::{Aabb, HitRecord, Hittable, Interval, Material, Onb, Ray, Vec3};
+
+/// An infinite plane through `point`. UVs are plane coordinates in world units.
+pub struct Plane<M: Material> {
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub material: M,
+    onb: Onb,
+}
+
+impl<M: Material> Plane<M> {
+    pub fn new(point: Vec3, normal: Vec3, material: M) -> Self {
+        let normal = normal.unit();
+        Self {
+            point,
+            normal,
+            material,
+            onb: Onb::new(normal),
+        }
+    }
+}
+
+impl<M: Material> Hittable for Plane<M> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let denom = self.normal.dot(ray.direction);
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = self.normal.dot(self.point - ray.origin) / denom;
+        if !ray_t.surrounds(t) {
+            return None;
+        }
+
+        let p = ray.at(t);
+        let offset = p - self.point;
+
+        let mut hr = HitRecord {
+            normal: self.normal,
+            p,
+            t,
+            u: offset.dot(self.onb.u),
+            v: offset.dot(self.onb.v),
+            front_face: false,
+            material: &self.material,
+        };
+        hr.set_face_normal(ray, self.normal);
+
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::universe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lambertian;
+
+    #[test]
+    fn test_hit() {
+        let plane = Plane::new(
+            Vec3::zero(),
+            Vec3::new(0.0, 1.0, 0.0),
+            Lambertian::new(Vec3::ones()),
+        );
+        let ray = Ray::new(Vec3::new(2.0, -1.0, 3.0), Vec3::new(0.0, 1.0, 0.0));
+        let hr = plane.hit(&ray, Interval::new(0.0, f64::INFINITY)).unwrap();
+        assert_eq!(hr.t, 1.0);
+        assert!(!hr.front_face);
+        assert_eq!(hr.normal, Vec3::new(0.0, -1.0, 0.0));
+        // UVs are distances along the plane from `point`.
+        assert!((hr.u * hr.u + hr.v * hr.v - 13.0).abs() < 1e-9);
+
+        let parallel = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(plane
+            .hit(&parallel, Interval::new(0.0, f64::INFINITY))
+            .is_none());
+    }
+}
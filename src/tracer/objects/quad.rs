@@ -0,0 +1,156 @@
+use crate::{random_f64, Aabb, HitRecord, Hittable, Interval, Material, Point3, Ray, Vec3};
+
+/// A parallelogram spanned by the edge vectors `u` and `v` from the corner `q`.
+pub struct Quad<M: Material> {
+    pub q: Vec3,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub material: M,
+    normal: Vec3,
+    d: f64,
+    w: Vec3,
+}
+
+impl<M: Material> Quad<M> {
+    pub fn new(q: Vec3, u: Vec3, v: Vec3, material: M) -> Self {
+        let n = u.cross(v);
+        let normal = n.unit();
+        let d = normal.dot(q);
+        let w = n / n.dot(n);
+        Self {
+            q,
+            u,
+            v,
+            material,
+            normal,
+            d,
+            w,
+        }
+    }
+}
+
+impl<M: Material> Hittable for Quad<M> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let denom = self.normal.dot(ray.direction);
+
+        // No hit if the ray is parallel to the plane.
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        // Return None if the hit point parameter t is outside the ray interval.
+        let t = (self.d - self.normal.dot(ray.origin)) / denom;
+        if !ray_t.surrounds(t) {
+            return None;
+        }
+
+        // Determine if the hit point lies within the planar shape using its plane coordinates.
+        let intersection = ray.at(t);
+        let planar_hitpt_vector = intersection - self.q;
+        let alpha = self.w.dot(planar_hitpt_vector.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar_hitpt_vector));
+
+        let unit_interval = Interval::new(0.0, 1.0);
+        if !unit_interval.contains(alpha) || !unit_interval.contains(beta) {
+            return None;
+        }
+
+        let mut hr = HitRecord {
+            normal: self.normal,
+            p: intersection,
+            t,
+            u: alpha,
+            v: beta,
+            front_face: false,
+            material: &self.material,
+        };
+        hr.set_face_normal(ray, self.normal);
+
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Compute the bounding box of all four vertices.
+        let bbox_diagonal1 = Aabb::from_points(self.q, self.q + self.u + self.v);
+        let bbox_diagonal2 = Aabb::from_points(self.q + self.u, self.q + self.v);
+        Aabb::surrounding(&bbox_diagonal1, &bbox_diagonal2)
+    }
+
+    fn is_emissive(&self) -> bool {
+        self.material.is_emissive()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let ray = Ray::new(origin, direction);
+        let Some(hr) = self.hit(&ray, Interval::new(0.001, f64::INFINITY)) else {
+            return 0.0;
+        };
+
+        // Convert the uniform density over the area to solid angle.
+        let distance_squared = hr.t * hr.t * direction.length_squard();
+        let cosine = (direction.dot(hr.normal) / direction.length()).abs();
+        distance_squared / (cosine * self.area())
+    }
+
+    fn random(&self, origin: Point3) -> Vec3 {
+        let p = self.q + (random_f64() * self.u) + (random_f64() * self.v);
+        p - origin
+    }
+
+    fn area(&self) -> f64 {
+        self.u.cross(self.v).length()
+    }
+
+    fn sample_surface(&self) -> Option<HitRecord<'_>> {
+        let (u, v) = (random_f64(), random_f64());
+        Some(HitRecord {
+            normal: self.normal,
+            p: self.q + (u * self.u) + (v * self.v),
+            t: 0.0,
+            u,
+            v,
+            front_face: true,
+            material: &self.material,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lambertian;
+
+    fn test_quad() -> Quad<Lambertian> {
+        Quad::new(
+            Vec3::new(-1.0, -1.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Lambertian::new(Vec3::ones()),
+        )
+    }
+
+    #[test]
+    fn test_hit() {
+        let quad = test_quad();
+        let ray = Ray::new(Vec3::new(0.5, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let hr = quad.hit(&ray, Interval::new(0.0, f64::INFINITY)).unwrap();
+        assert_eq!(hr.t, 5.0);
+        // The ray arrives from behind the u x v side.
+        assert!(!hr.front_face);
+        assert_eq!(hr.normal, Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!((hr.u, hr.v), (0.75, 0.5));
+    }
+
+    #[test]
+    fn test_miss() {
+        let quad = test_quad();
+        let outside = Ray::new(Vec3::new(1.5, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(quad
+            .hit(&outside, Interval::new(0.0, f64::INFINITY))
+            .is_none());
+
+        // A hit exactly at the end of the interval does not count.
+        let ray = Ray::new(Vec3::new(0.5, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(quad.hit(&ray, Interval::new(0.0, 5.0)).is_none());
+    }
+}
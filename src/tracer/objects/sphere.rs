@@ -1,6 +1,9 @@
+use std::f64::consts::PI;
+
 use crate::{
+    random_to_sphere, random_uint_vector,
     tracer::{hit_record::HitRecord, hittable::Hittable, ray::Ray, vec3::Vec3},
-    Interval, Material,
+    Aabb, Interval, Material, Onb, Point3,
 };
 
 pub struct Sphere<M: Material> {
@@ -20,42 +23,120 @@ impl<M: Material> Sphere<M> {
 }
 
 impl<M: Material> Hittable for Sphere<M> {
-    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord> {
-        let oc = self.center - ray.origin;
-        let a = ray.direction.length_squard();
-        let h = ray.direction.dot(oc);
-        let c = oc.length_squard() - self.radius * self.radius;
-
-        let discriminant = h * h - a * c;
-        if discriminant < 0.0 {
-            return None;
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        hit_sphere(self.center, self.radius, &self.material, ray, ray_t)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let rvec = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::from_points(self.center - rvec, self.center + rvec)
+    }
+
+    fn is_emissive(&self) -> bool {
+        self.material.is_emissive()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let ray = Ray::new(origin, direction);
+        if self
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .is_none()
+        {
+            return 0.0;
         }
 
-        let sqrtd = discriminant.sqrt();
+        let distance_squared = (self.center - origin).length_squard();
+        if distance_squared <= self.radius * self.radius {
+            // From inside, every direction hits the sphere.
+            return 1.0 / (4.0 * PI);
+        }
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared).sqrt();
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+        1.0 / solid_angle
+    }
 
-        // Find the nearest root that lies in the acceptable range.
-        let mut root = (h - sqrtd) / a;
-        if !ray_t.surrounds(root) {
-            root = (h + sqrtd) / a;
-            if !ray_t.surrounds(root) {
-                return None;
-            }
+    fn random(&self, origin: Point3) -> Vec3 {
+        let direction = self.center - origin;
+        let distance_squared = direction.length_squard();
+        if distance_squared <= self.radius * self.radius {
+            return random_uint_vector();
         }
+        Onb::new(direction).transform(random_to_sphere(self.radius, distance_squared))
+    }
 
-        let p = ray.at(root);
-        let outward_normal = (p - self.center) / self.radius;
-        let t = root;
+    fn area(&self) -> f64 {
+        4.0 * PI * self.radius * self.radius
+    }
 
-        let mut hr = HitRecord {
-            normal: outward_normal,
-            p,
-            t,
-            front_face: false,
+    fn sample_surface(&self) -> Option<HitRecord<'_>> {
+        let normal = random_uint_vector();
+        let (u, v) = get_sphere_uv(normal);
+        Some(HitRecord {
+            normal,
+            p: self.center + self.radius * normal,
+            t: 0.0,
+            u,
+            v,
+            front_face: true,
             material: &self.material,
-        };
+        })
+    }
+}
+
+/// Intersects a ray with a sphere; shared by the static and moving spheres.
+pub(crate) fn hit_sphere<'a>(
+    center: Vec3,
+    radius: f64,
+    material: &'a dyn Material,
+    ray: &Ray,
+    ray_t: Interval,
+) -> Option<HitRecord<'a>> {
+    let oc = center - ray.origin;
+    let a = ray.direction.length_squard();
+    let h = ray.direction.dot(oc);
+    let c = oc.length_squard() - radius * radius;
+
+    let discriminant = h * h - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
 
-        hr.set_face_normal(ray, outward_normal);
+    let sqrtd = discriminant.sqrt();
 
-        Some(hr)
+    // Find the nearest root that lies in the acceptable range.
+    let mut root = (h - sqrtd) / a;
+    if !ray_t.surrounds(root) {
+        root = (h + sqrtd) / a;
+        if !ray_t.surrounds(root) {
+            return None;
+        }
     }
+
+    let p = ray.at(root);
+    let outward_normal = (p - center) / radius;
+    let t = root;
+    let (u, v) = get_sphere_uv(outward_normal);
+
+    let mut hr = HitRecord {
+        normal: outward_normal,
+        p,
+        t,
+        u,
+        v,
+        front_face: false,
+        material,
+    };
+
+    hr.set_face_normal(ray, outward_normal);
+
+    Some(hr)
+}
+
+/// Returns the (u, v) texture coordinates of a point `p` on the unit sphere.
+/// u: returned value [0,1] of angle around the Y axis from X=-1.
+/// v: returned value [0,1] of angle from Y=-1 to Y=+1.
+pub fn get_sphere_uv(p: Vec3) -> (f64, f64) {
+    let theta = (-p.y).acos();
+    let phi = (-p.z).atan2(p.x) + PI;
+    (phi / (2.0 * PI), theta / PI)
 }
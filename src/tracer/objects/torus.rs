@@ -0,0 +1,162 @@
+use std::f64::consts::PI;
+
+use crate::{solve_quartic, Aabb, HitRecord, Hittable, Interval, Material, Onb, Ray, Vec3};
+
+/// A ring torus around `center`, with the ring lying in the plane facing `axis`.
+pub struct Torus<M: Material> {
+    pub center: Vec3,
+    pub axis: Vec3,
+    pub major_radius: f64, // Distance from the center to the middle of the tube
+    pub minor_radius: f64, // Radius of the tube
+    pub material: M,
+    onb: Onb,
+}
+
+impl<M: Material> Torus<M> {
+    pub fn new(
+        center: Vec3,
+        axis: Vec3,
+        major_radius: f64,
+        minor_radius: f64,
+        material: M,
+    ) -> Self {
+        Self {
+            center,
+            axis: axis.unit(),
+            major_radius: major_radius.max(0.0),
+            minor_radius: minor_radius.max(0.0),
+            material,
+            onb: Onb::new(axis),
+        }
+    }
+}
+
+impl<M: Material> Hittable for Torus<M> {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        // Skip the quartic for rays that miss the bounds entirely.
+        self.bounding_box().hit(ray, ray_t)?;
+
+        // Work with a unit direction for a well-conditioned quartic, then rescale t.
+        let length = ray.direction.length();
+        let o = self.onb.to_local(ray.origin - self.center);
+        let d = self.onb.to_local(ray.direction / length);
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + y^2), with p = o + t d.
+        let r2 = self.major_radius * self.major_radius;
+        let four_r2 = 4.0 * r2;
+        let f = o.dot(d);
+        let oo = o.length_squard();
+        let k = oo + r2 - self.minor_radius * self.minor_radius;
+        let coefficients = [
+            k * k - four_r2 * (oo - o.z * o.z),
+            4.0 * f * k - four_r2 * 2.0 * (f - o.z * d.z),
+            4.0 * f * f + 2.0 * k - four_r2 * (1.0 - d.z * d.z),
+            4.0 * f,
+            1.0,
+        ];
+
+        let t = solve_quartic(coefficients)
+            .into_iter()
+            .map(|s| s / length)
+            .filter(|&t| ray_t.surrounds(t))
+            .min_by(|a, b| a.partial_cmp(b).unwrap())?;
+
+        let lp = o + (t * length) * d;
+        let ring = lp.x.hypot(lp.y);
+
+        // Gradient of the implicit surface, pointing out of the tube.
+        let s = lp.length_squard() - r2 - self.minor_radius * self.minor_radius;
+        let local_normal = Vec3::new(lp.x * s, lp.y * s, lp.z * (s + 2.0 * r2)).unit();
+
+        // u runs around the ring, v around the tube.
+        let u = (lp.y.atan2(lp.x) + PI) / (2.0 * PI);
+        let v = (lp.z.atan2(ring - self.major_radius) + PI) / (2.0 * PI);
+
+        let outward_normal = self.onb.transform(local_normal);
+        let mut hr = HitRecord {
+            normal: outward_normal,
+            p: ray.at(t),
+            t,
+            u,
+            v,
+            front_face: false,
+            material: &self.material,
+        };
+        hr.set_face_normal(ray, outward_normal);
+
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let ring = Aabb::from_disk(
+            self.center,
+            self.axis,
+            self.major_radius + self.minor_radius,
+        );
+        let r = self.minor_radius;
+        let tube = Vec3::new(
+            r * self.axis.x.abs(),
+            r * self.axis.y.abs(),
+            r * self.axis.z.abs(),
+        );
+        Aabb::from_points(
+            Vec3::new(ring.x.min, ring.y.min, ring.z.min) - tube,
+            Vec3::new(ring.x.max, ring.y.max, ring.z.max) + tube,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lambertian;
+
+    fn test_torus() -> Torus<Lambertian> {
+        Torus::new(
+            Vec3::zero(),
+            Vec3::new(0.0, 1.0, 0.0),
+            2.0,
+            0.5,
+            Lambertian::new(Vec3::ones()),
+        )
+    }
+
+    fn hit(ray: Ray) -> Option<(f64, Vec3, bool)> {
+        let torus = test_torus();
+        let hr = torus.hit(&ray, Interval::new(0.001, f64::INFINITY))?;
+        Some((hr.t, hr.normal, hr.front_face))
+    }
+
+    #[test]
+    fn test_outer_side() {
+        let ray = Ray::new(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let (t, normal, front_face) = hit(ray).unwrap();
+        assert!((t - 2.5).abs() < 1e-6);
+        assert!((normal - Vec3::new(-1.0, 0.0, 0.0)).length() < 1e-6);
+        assert!(front_face);
+    }
+
+    #[test]
+    fn test_from_inside_hole() {
+        // The inner wall of the tube faces the center of the hole.
+        let ray = Ray::new(Vec3::zero(), Vec3::new(1.0, 0.0, 0.0));
+        let (t, normal, front_face) = hit(ray).unwrap();
+        assert!((t - 1.5).abs() < 1e-6);
+        assert!((normal - Vec3::new(-1.0, 0.0, 0.0)).length() < 1e-6);
+        assert!(front_face);
+    }
+
+    #[test]
+    fn test_top_of_tube() {
+        let ray = Ray::new(Vec3::new(2.0, 5.0, 0.0), Vec3::new(0.0, -2.0, 0.0));
+        let (t, normal, _) = hit(ray).unwrap();
+        assert!((t - 2.25).abs() < 1e-6);
+        assert!((normal - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-6);
+    }
+
+    #[test]
+    fn test_miss_through_hole() {
+        let ray = Ray::new(Vec3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert!(hit(ray).is_none());
+    }
+}
@@ -0,0 +1,696 @@
+use std::sync::Arc;
+
+use crate::{
+    Aabb, HitRecord, Hittable, Interval, Mat3, Mat4, Material, Point3, Quaternion, Ray, Vec3,
+};
+
+/// Moves a shared object by a fixed offset, optionally replacing its material.
+pub struct Translate {
+    pub object: Arc<dyn Hittable>,
+    pub offset: Vec3,
+    pub material: Option<Arc<dyn Material>>, // Overrides the object's own material
+}
+
+impl Translate {
+    pub fn new(object: Arc<dyn Hittable>, offset: Vec3) -> Self {
+        Self {
+            object,
+            offset,
+            material: None,
+        }
+    }
+
+    pub fn with_material(mut self, material: Arc<dyn Material>) -> Self {
+        self.material = Some(material);
+        self
+    }
+}
+
+impl Hittable for Translate {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        // Move the ray backwards by the offset, intersect, then move the hit point forwards.
+        let offset_ray = Ray::with_time(ray.origin - self.offset, ray.direction, ray.time);
+        let mut hr = self.object.hit(&offset_ray, ray_t)?;
+        hr.p += self.offset;
+        if let Some(material) = &self.material {
+            hr.material = material.as_ref();
+        }
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let bbox = self.object.bounding_box();
+        Aabb::from_points(
+            Vec3::new(bbox.x.min, bbox.y.min, bbox.z.min) + self.offset,
+            Vec3::new(bbox.x.max, bbox.y.max, bbox.z.max) + self.offset,
+        )
+    }
+
+    fn is_emissive(&self) -> bool {
+        self.object.is_emissive()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        self.object.pdf_value(origin - self.offset, direction)
+    }
+
+    fn random(&self, origin: Point3) -> Vec3 {
+        self.object.random(origin - self.offset)
+    }
+
+    fn area(&self) -> f64 {
+        self.object.area()
+    }
+
+    fn sample_surface(&self) -> Option<HitRecord<'_>> {
+        let mut hr = self.object.sample_surface()?;
+        hr.p += self.offset;
+        Some(hr)
+    }
+}
+
+/// Rotates a shared object around an arbitrary axis through the origin, optionally
+/// replacing its material.
+pub struct Rotate {
+    pub object: Arc<dyn Hittable>,
+    pub material: Option<Arc<dyn Material>>, // Overrides the object's own material
+    rotation: Mat4,
+    inverse: Mat4,
+    bbox: Aabb,
+}
+
+impl Rotate {
+    pub fn new(object: Arc<dyn Hittable>, axis: Vec3, degrees: f64) -> Self {
+        let rotation = Mat4::rotation(axis, degrees);
+        let bbox = transform_bounding_box(&rotation, &object.bounding_box());
+        Self {
+            object,
+            material: None,
+            rotation,
+            inverse: rotation.transpose(),
+            bbox,
+        }
+    }
+
+    pub fn with_material(mut self, material: Arc<dyn Material>) -> Self {
+        self.material = Some(material);
+        self
+    }
+}
+
+impl Hittable for Rotate {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let rotated_ray = Ray::with_time(
+            self.inverse.transform_vector(ray.origin),
+            self.inverse.transform_vector(ray.direction),
+            ray.time,
+        );
+        let mut hr = self.object.hit(&rotated_ray, ray_t)?;
+        hr.p = self.rotation.transform_vector(hr.p);
+        hr.normal = self.rotation.transform_vector(hr.normal);
+        if let Some(material) = &self.material {
+            hr.material = material.as_ref();
+        }
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn is_emissive(&self) -> bool {
+        self.object.is_emissive()
+    }
+
+    // Rotations preserve solid angle, so densities carry over unchanged.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        self.object.pdf_value(
+            self.inverse.transform_vector(origin),
+            self.inverse.transform_vector(direction),
+        )
+    }
+
+    fn random(&self, origin: Point3) -> Vec3 {
+        let direction = self.object.random(self.inverse.transform_vector(origin));
+        self.rotation.transform_vector(direction)
+    }
+
+    fn area(&self) -> f64 {
+        self.object.area()
+    }
+
+    fn sample_surface(&self) -> Option<HitRecord<'_>> {
+        let mut hr = self.object.sample_surface()?;
+        hr.p = self.rotation.transform_vector(hr.p);
+        hr.normal = self.rotation.transform_vector(hr.normal);
+        Some(hr)
+    }
+}
+
+/// Places a shared object with a general affine transform, optionally replacing its
+/// material. Many `Transform`s can point at the same geometry, so scattering copies of
+/// an object costs one matrix each rather than a copy of the object.
+pub struct Transform {
+    pub object: Arc<dyn Hittable>,
+    pub material: Option<Arc<dyn Material>>, // Overrides the object's own material
+    matrix: Mat4,
+    inverse: Mat4,
+    bbox: Aabb,
+    area_scale: f64, // Factor areas grow by, or 0 if the matrix shears or stretches
+}
+
+impl Transform {
+    /// Panics if `matrix` is singular.
+    pub fn new(object: Arc<dyn Hittable>, matrix: Mat4) -> Self {
+        let inverse = matrix
+            .inverse()
+            .expect("transform matrix must be invertible");
+        let bbox = transform_bounding_box(&matrix, &object.bounding_box());
+        Self {
+            object,
+            material: None,
+            matrix,
+            inverse,
+            bbox,
+            area_scale: uniform_area_scale(&matrix).unwrap_or(0.0),
+        }
+    }
+
+    pub fn with_material(mut self, material: Arc<dyn Material>) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    pub fn matrix(&self) -> &Mat4 {
+        &self.matrix
+    }
+}
+
+impl Hittable for Transform {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        // The direction is left unnormalized so t means the same thing in both spaces.
+        let object_ray = Ray::with_time(
+            self.inverse.transform_point(ray.origin),
+            self.inverse.transform_vector(ray.direction),
+            ray.time,
+        );
+        let mut hr = self.object.hit(&object_ray, ray_t)?;
+
+        hr.p = self.matrix.transform_point(hr.p);
+        hr.normal = self.inverse.transform_normal_by_inverse(hr.normal).unit();
+        if let Some(material) = &self.material {
+            hr.material = material.as_ref();
+        }
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+
+    fn is_emissive(&self) -> bool {
+        self.object.is_emissive()
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let object_direction = self.inverse.transform_vector(direction);
+        let pdf = self
+            .object
+            .pdf_value(self.inverse.transform_point(origin), object_direction);
+        if pdf == 0.0 {
+            return 0.0;
+        }
+        // A linear map A stretches solid angle around the unit direction w by
+        // |det A| / |A w|^3, and A takes object_direction / |object_direction| to
+        // direction / |object_direction|.
+        let stretch = direction.length() / object_direction.length();
+        pdf * stretch.powi(3) / linear_determinant(&self.matrix).abs()
+    }
+
+    fn random(&self, origin: Point3) -> Vec3 {
+        let direction = self.object.random(self.inverse.transform_point(origin));
+        self.matrix.transform_vector(direction)
+    }
+
+    // Only rigid motions and uniform scales stretch every surface by the same factor;
+    // other matrices would need the area of the transformed surface itself, so such
+    // objects are not sampled by area.
+    fn area(&self) -> f64 {
+        self.area_scale * self.object.area()
+    }
+
+    fn sample_surface(&self) -> Option<HitRecord<'_>> {
+        if self.area_scale == 0.0 {
+            return None;
+        }
+        let mut hr = self.object.sample_surface()?;
+        hr.p = self.matrix.transform_point(hr.p);
+        hr.normal = self.inverse.transform_normal_by_inverse(hr.normal).unit();
+        if let Some(material) = &self.material {
+            hr.material = material.as_ref();
+        }
+        Some(hr)
+    }
+}
+
+/// One pose of an `AnimatedTransform`, applied as scale, then rotation, then translation.
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    pub time: f64,
+    pub translation: Vec3,
+    pub rotation: Quaternion,
+    pub scale: Vec3,
+}
+
+impl Keyframe {
+    pub fn new(time: f64, translation: Vec3, rotation: Quaternion, scale: Vec3) -> Self {
+        Self {
+            time,
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    fn matrix(&self) -> Mat4 {
+        Mat4::translation(self.translation) * self.rotation.to_mat4() * Mat4::scaling(self.scale)
+    }
+
+    /// Inverse of `matrix`, built from the inverted parts rather than by elimination.
+    /// Returns `None` if a scale component is zero.
+    fn inverse_matrix(&self) -> Option<Mat4> {
+        if self.scale.x == 0.0 || self.scale.y == 0.0 || self.scale.z == 0.0 {
+            return None;
+        }
+        let inverse_scale = Vec3::new(1.0 / self.scale.x, 1.0 / self.scale.y, 1.0 / self.scale.z);
+        Some(
+            Mat4::scaling(inverse_scale)
+                * self.rotation.conjugate().to_mat4()
+                * Mat4::translation(-self.translation),
+        )
+    }
+
+    /// Blends translation and scale linearly and rotation along the shortest arc.
+    fn interpolate(&self, other: &Keyframe, time: f64) -> Keyframe {
+        let a = (time - self.time) / (other.time - self.time);
+        Keyframe {
+            time,
+            translation: self.translation * (1.0 - a) + other.translation * a,
+            rotation: Quaternion::slerp(self.rotation, other.rotation, a),
+            scale: self.scale * (1.0 - a) + other.scale * a,
+        }
+    }
+}
+
+/// Like `Transform`, but the placement is interpolated between keyframes at each
+/// ray's time, so the object moves during the exposure.
+///
+/// Emissive objects placed this way are not sampled as lights, since light sampling
+/// does not know the time of the path; they still light the scene when paths hit them.
+pub struct AnimatedTransform {
+    pub object: Arc<dyn Hittable>,
+    pub material: Option<Arc<dyn Material>>, // Overrides the object's own material
+    keyframes: Vec<Keyframe>,
+    bbox: Aabb,
+}
+
+impl AnimatedTransform {
+    /// Panics if `keyframes` is empty.
+    pub fn new(object: Arc<dyn Hittable>, mut keyframes: Vec<Keyframe>) -> Self {
+        assert!(
+            !keyframes.is_empty(),
+            "an animated transform needs a keyframe"
+        );
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        let object_bbox = object.bounding_box();
+        let mut bbox = transform_bounding_box(&keyframes[0].matrix(), &object_bbox);
+        for pair in keyframes.windows(2) {
+            bbox = Aabb::surrounding(&bbox, &swept_bounding_box(&pair[0], &pair[1], &object_bbox));
+        }
+
+        Self {
+            object,
+            material: None,
+            keyframes,
+            bbox,
+        }
+    }
+
+    pub fn with_material(mut self, material: Arc<dyn Material>) -> Self {
+        self.material = Some(material);
+        self
+    }
+
+    pub fn matrix_at(&self, time: f64) -> Mat4 {
+        self.keyframe_at(time).matrix()
+    }
+
+    fn keyframe_at(&self, time: f64) -> Keyframe {
+        let keys = &self.keyframes;
+        let next = keys.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return keys[0];
+        }
+        if next == keys.len() {
+            return keys[keys.len() - 1];
+        }
+        keys[next - 1].interpolate(&keys[next], time)
+    }
+}
+
+impl Hittable for AnimatedTransform {
+    fn hit(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let pose = self.keyframe_at(ray.time);
+        let matrix = pose.matrix();
+        let inverse = pose.inverse_matrix()?;
+        let object_ray = Ray::with_time(
+            inverse.transform_point(ray.origin),
+            inverse.transform_vector(ray.direction),
+            ray.time,
+        );
+        let mut hr = self.object.hit(&object_ray, ray_t)?;
+
+        hr.p = matrix.transform_point(hr.p);
+        hr.normal = inverse.transform_normal_by_inverse(hr.normal).unit();
+        if let Some(material) = &self.material {
+            hr.material = material.as_ref();
+        }
+        Some(hr)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+/// Determinant of the linear (upper-left 3x3) part of `matrix`.
+fn linear_determinant(matrix: &Mat4) -> f64 {
+    let m = &matrix.m;
+    Mat3::new([
+        [m[0][0], m[0][1], m[0][2]],
+        [m[1][0], m[1][1], m[1][2]],
+        [m[2][0], m[2][1], m[2][2]],
+    ])
+    .determinant()
+}
+
+/// The square of the scale factor if the linear part of `matrix` is a rotation or
+/// reflection times a uniform scale, which multiplies every area by the same amount.
+fn uniform_area_scale(matrix: &Mat4) -> Option<f64> {
+    let m = &matrix.m;
+    let columns: [Vec3; 3] = std::array::from_fn(|j| Vec3::new(m[0][j], m[1][j], m[2][j]));
+    let scale = columns[0].length_squard();
+    let tolerance = 1e-9 * scale;
+    for (i, a) in columns.iter().enumerate() {
+        for (j, b) in columns.iter().enumerate() {
+            let expected = if i == j { scale } else { 0.0 };
+            if (a.dot(*b) - expected).abs() > tolerance {
+                return None;
+            }
+        }
+    }
+    Some(scale)
+}
+
+/// Bounds of everything `bbox` sweeps through between poses `a` and `b`.
+fn swept_bounding_box(a: &Keyframe, b: &Keyframe, bbox: &Aabb) -> Aabb {
+    let at_a = transform_bounding_box(&a.matrix(), bbox);
+    let at_b = transform_bounding_box(&b.matrix(), bbox);
+    let ends = Aabb::surrounding(&at_a, &at_b);
+    if a.rotation == b.rotation {
+        // Translation and scale blend linearly, so every corner moves in a straight
+        // line and the two end poses bound the whole sweep.
+        return ends;
+    }
+    if ends.x.max.is_infinite() {
+        return ends;
+    }
+
+    // While rotating, a point stays within its (largest) scaled distance of the
+    // object's origin, which itself only moves along the translation segment.
+    let scale = Vec3::new(
+        a.scale.x.abs().max(b.scale.x.abs()),
+        a.scale.y.abs().max(b.scale.y.abs()),
+        a.scale.z.abs().max(b.scale.z.abs()),
+    );
+    let mut radius: f64 = 0.0;
+    for i in 0..8 {
+        let corner = Vec3::new(
+            if i & 1 == 0 { bbox.x.min } else { bbox.x.max },
+            if i & 2 == 0 { bbox.y.min } else { bbox.y.max },
+            if i & 4 == 0 { bbox.z.min } else { bbox.z.max },
+        );
+        radius = radius.max((scale * corner).length());
+    }
+    let r = Vec3::new(radius, radius, radius);
+    Aabb::surrounding(
+        &Aabb::from_points(a.translation - r, a.translation + r),
+        &Aabb::from_points(b.translation - r, b.translation + r),
+    )
+}
+
+/// Bounds of the eight transformed corners of `bbox`. Unbounded boxes stay unbounded.
+fn transform_bounding_box(matrix: &Mat4, bbox: &Aabb) -> Aabb {
+    let axes = [bbox.x, bbox.y, bbox.z];
+    if axes
+        .iter()
+        .any(|a| a.min.is_infinite() || a.max.is_infinite())
+    {
+        return Aabb::universe();
+    }
+
+    let mut min = Vec3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+    let mut max = -min;
+    for i in 0..8 {
+        let corner = Vec3::new(
+            if i & 1 == 0 { bbox.x.min } else { bbox.x.max },
+            if i & 2 == 0 { bbox.y.min } else { bbox.y.max },
+            if i & 4 == 0 { bbox.z.min } else { bbox.z.max },
+        );
+        let p = matrix.transform_point(corner);
+        for axis in 0..3 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+    }
+    Aabb::from_points(min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{degrees_to_radians, Lambertian, Quad, Sphere};
+
+    /// A unit square in the z = 0 plane, facing +z, centered on the origin.
+    fn square() -> Arc<dyn Hittable> {
+        Arc::new(Quad::new(
+            Vec3::new(-0.5, -0.5, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Lambertian::new(Vec3::ones()),
+        ))
+    }
+
+    fn red() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(Vec3::new(1.0, 0.0, 0.0)))
+    }
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_translate_hit() {
+        let object = Translate::new(square(), Vec3::new(0.0, 0.0, 2.0)).with_material(red());
+        let ray = Ray::new(Vec3::new(0.2, 0.1, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let hr = object
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!((hr.t - 3.0).abs() < 1e-9);
+        assert_close(hr.p, Vec3::new(0.2, 0.1, 2.0));
+        assert_close(hr.normal, Vec3::new(0.0, 0.0, 1.0));
+        assert_close(hr.material.albedo(&hr), Vec3::new(1.0, 0.0, 0.0));
+
+        let miss = Ray::new(Vec3::new(0.2, 0.1, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(object
+            .hit(&miss, Interval::new(0.001, f64::INFINITY))
+            .is_none());
+    }
+
+    #[test]
+    fn test_rotate_hit() {
+        // A quarter turn around y swings the square's normal from +z to +x.
+        let object = Rotate::new(square(), Vec3::new(0.0, 1.0, 0.0), 90.0).with_material(red());
+        let ray = Ray::new(Vec3::new(5.0, 0.2, 0.1), Vec3::new(-1.0, 0.0, 0.0));
+        let hr = object
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!((hr.t - 5.0).abs() < 1e-9);
+        assert_close(hr.p, Vec3::new(0.0, 0.2, 0.1));
+        assert_close(hr.normal, Vec3::new(1.0, 0.0, 0.0));
+        assert!(hr.front_face);
+        assert_close(hr.material.albedo(&hr), Vec3::new(1.0, 0.0, 0.0));
+
+        let unrotated = Rotate::new(square(), Vec3::new(0.0, 1.0, 0.0), 90.0);
+        let hr = unrotated
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert_close(hr.material.albedo(&hr), Vec3::ones());
+    }
+
+    #[test]
+    fn test_transform_hit() {
+        // Stretching along x must not skew the normal of a plane tilted off the axes.
+        let matrix = Mat4::translation(Vec3::new(0.0, 0.0, -1.0))
+            * Mat4::scaling(Vec3::new(2.0, 1.0, 1.0))
+            * Mat4::rotation(Vec3::new(0.0, 1.0, 0.0), 45.0);
+        let object = Transform::new(square(), matrix).with_material(red());
+        let origin = Vec3::new(0.0, 0.0, 5.0);
+        let ray = Ray::new(origin, Vec3::new(0.0, 0.0, -1.0));
+        let hr = object
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!((hr.t - 6.0).abs() < 1e-9);
+        assert_close(hr.p, Vec3::new(0.0, 0.0, -1.0));
+        assert_close(hr.normal, Vec3::new(1.0, 0.0, 2.0).unit());
+        assert_close(hr.material.albedo(&hr), Vec3::new(1.0, 0.0, 0.0));
+    }
+
+    /// A small ball on a 2-unit arm that swings 170 degrees around z over the exposure.
+    fn swinging_ball() -> AnimatedTransform {
+        let ball: Arc<dyn Hittable> = Arc::new(Sphere::new(
+            Vec3::new(2.0, 0.0, 0.0),
+            0.1,
+            Lambertian::new(Vec3::ones()),
+        ));
+        let axis = Vec3::new(0.0, 0.0, 1.0);
+        AnimatedTransform::new(
+            ball,
+            vec![
+                Keyframe::new(0.0, Vec3::zero(), Quaternion::identity(), Vec3::ones()),
+                Keyframe::new(
+                    1.0,
+                    Vec3::new(0.0, 0.0, 1.0),
+                    Quaternion::from_axis_angle(axis, 170.0),
+                    Vec3::ones(),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_animated_hit_at_time() {
+        let object = swinging_ball();
+        // Halfway through, the arm has turned 85 degrees and risen by half a unit.
+        let center = object
+            .matrix_at(0.5)
+            .transform_point(Vec3::new(2.0, 0.0, 0.0));
+        let (sin, cos) = degrees_to_radians(85.0).sin_cos();
+        assert_close(center, Vec3::new(2.0 * cos, 2.0 * sin, 0.5));
+
+        let ray = Ray::with_time(
+            center + Vec3::new(0.0, 0.0, 5.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            0.5,
+        );
+        let hr = object
+            .hit(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!((hr.t - 4.9).abs() < 1e-9);
+        assert_close(hr.normal, Vec3::new(0.0, 0.0, 1.0));
+
+        // At the start of the exposure the ball is elsewhere.
+        let early = Ray::with_time(ray.origin, ray.direction, 0.0);
+        assert!(object
+            .hit(&early, Interval::new(0.001, f64::INFINITY))
+            .is_none());
+    }
+
+    #[test]
+    fn test_animated_bounding_box_covers_sweep() {
+        let object = swinging_ball();
+        let bbox = object.bounding_box();
+        for step in 0..=1000 {
+            let time = step as f64 / 1000.0;
+            let c = object
+                .matrix_at(time)
+                .transform_point(Vec3::new(2.0, 0.0, 0.0));
+            for (axis, value) in [(&bbox.x, c.x), (&bbox.y, c.y), (&bbox.z, c.z)] {
+                assert!(axis.min <= value - 0.1 && value + 0.1 <= axis.max);
+            }
+        }
+    }
+
+    #[test]
+    fn test_keyframe_inverse() {
+        let pose = Keyframe::new(
+            0.0,
+            Vec3::new(1.0, -2.0, 3.0),
+            Quaternion::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 40.0),
+            Vec3::new(2.0, 0.5, 1.5),
+        );
+        let product = pose.matrix() * pose.inverse_matrix().unwrap();
+        let identity = Mat4::identity();
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((product.m[i][j] - identity.m[i][j]).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transform_light_pdf() {
+        let light: Arc<dyn Hittable> = Arc::new(Quad::new(
+            Vec3::new(-0.5, -0.5, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            crate::DiffuseLight::new(Vec3::ones()),
+        ));
+        // Scaling the square up to 2x2 spreads the same density over four times the area.
+        let object = Transform::new(light, Mat4::scaling(Vec3::new(2.0, 2.0, 1.0)));
+        assert!(object.is_emissive());
+
+        let origin = Vec3::new(0.0, 0.0, 3.0);
+        let pdf = object.pdf_value(origin, Vec3::new(0.0, 0.0, -2.0));
+        assert!((pdf - 9.0 / 4.0).abs() < 1e-9);
+
+        // Sampled directions land on the scaled square, with the density pdf_value reports.
+        for _ in 0..100 {
+            let direction = object.random(origin);
+            let hr = object
+                .hit(
+                    &Ray::new(origin, direction),
+                    Interval::new(0.001, f64::INFINITY),
+                )
+                .unwrap();
+            assert!(hr.p.x.abs() <= 1.0 && hr.p.y.abs() <= 1.0);
+            let cosine = direction.unit().z.abs();
+            let distance_squared = (hr.p - origin).length_squard();
+            let expected = distance_squared / (cosine * 4.0);
+            assert!((object.pdf_value(origin, direction) - expected).abs() < 1e-9 * expected);
+        }
+    }
+
+    #[test]
+    fn test_transform_surface_samples() {
+        // Turned, tripled and moved, the unit square lies in the x = 1 plane.
+        let matrix = Mat4::translation(Vec3::new(1.0, 0.0, 0.0))
+            * Mat4::scaling(Vec3::new(3.0, 3.0, 3.0))
+            * Mat4::rotation(Vec3::new(0.0, 1.0, 0.0), 90.0);
+        let object = Transform::new(square(), matrix).with_material(red());
+        assert!((object.area() - 9.0).abs() < 1e-9);
+        for _ in 0..100 {
+            let hr = object.sample_surface().unwrap();
+            assert!((hr.p.x - 1.0).abs() < 1e-9);
+            assert!(hr.p.y.abs() <= 1.5 && hr.p.z.abs() <= 1.5);
+            assert_close(hr.normal, Vec3::new(1.0, 0.0, 0.0));
+            assert_close(hr.material.albedo(&hr), Vec3::new(1.0, 0.0, 0.0));
+        }
+
+        // Stretching changes areas unevenly, so such objects are not sampled by area.
+        let stretched = Transform::new(square(), Mat4::scaling(Vec3::new(2.0, 1.0, 1.0)));
+        assert_eq!(stretched.area(), 0.0);
+        assert!(stretched.sample_surface().is_none());
+    }
+}
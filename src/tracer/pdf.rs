@@ -0,0 +1,99 @@
+use crate::Vec3;
+
+/// A probability density over directions, used to importance-sample scattering.
+pub trait Pdf {
+    /// Solid-angle density with which `generate` produces `direction`.
+    fn value(&self, direction: Vec3) -> f64;
+
+    /// Draws a direction distributed according to `value`.
+    fn generate(&self) -> Vec3;
+}
+
+/// Monte Carlo checks shared by the densities' tests.
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::SpherePdf;
+
+    /// Cells of the quadrature grid along `z` and around the axis. The cells are
+    /// equal-area, so each covers the same solid angle.
+    const GRID: (usize, usize) = (400, 800);
+
+    /// Coarse bins of the histogram; each is a block of grid cells.
+    const BINS: (usize, usize) = (8, 16);
+
+    fn bin(direction: Vec3) -> usize {
+        let d = direction.unit();
+        let i = (((d.z + 1.0) / 2.0 * BINS.0 as f64) as usize).min(BINS.0 - 1);
+        let phi = d.y.atan2(d.x) + PI;
+        let j = ((phi / (2.0 * PI) * BINS.1 as f64) as usize).min(BINS.1 - 1);
+        i * BINS.1 + j
+    }
+
+    /// The probability `pdf` assigns to each histogram bin, integrating `value` over
+    /// the grid.
+    fn expected_histogram(pdf: &dyn Pdf) -> Vec<f64> {
+        let d_omega = 4.0 * PI / (GRID.0 * GRID.1) as f64;
+        let mut bins = vec![0.0; BINS.0 * BINS.1];
+        for i in 0..GRID.0 {
+            let z = -1.0 + 2.0 * (i as f64 + 0.5) / GRID.0 as f64;
+            let r = (1.0 - z * z).sqrt();
+            for j in 0..GRID.1 {
+                let phi = 2.0 * PI * (j as f64 + 0.5) / GRID.1 as f64 - PI;
+                let direction = Vec3::new(r * phi.cos(), r * phi.sin(), z);
+                bins[bin(direction)] += pdf.value(direction) * d_omega;
+            }
+        }
+        bins
+    }
+
+    /// Integral of `value` over the sphere of directions.
+    pub(crate) fn integral(pdf: &dyn Pdf) -> f64 {
+        expected_histogram(pdf).iter().sum()
+    }
+
+    /// Total variation distance between the histogram of `n` directions from `generate`
+    /// and the one `value` predicts.
+    pub(crate) fn histogram_distance(pdf: &dyn Pdf, n: usize) -> f64 {
+        let expected = expected_histogram(pdf);
+        let mut observed = vec![0.0; expected.len()];
+        for _ in 0..n {
+            observed[bin(pdf.generate())] += 1.0 / n as f64;
+        }
+        0.5 * observed
+            .iter()
+            .zip(&expected)
+            .map(|(o, e)| (o - e).abs())
+            .sum::<f64>()
+    }
+
+    /// Asserts that `value` is normalized and describes what `generate` produces.
+    pub(crate) fn assert_consistent(pdf: &dyn Pdf) {
+        let integral = integral(pdf);
+        assert!((integral - 1.0).abs() < 0.01, "integral {integral}");
+        let distance = histogram_distance(pdf, 100_000);
+        assert!(distance < 0.04, "histogram distance {distance}");
+    }
+
+    /// Claims to be uniform but only generates the upper hemisphere.
+    struct Mislabelled;
+
+    impl Pdf for Mislabelled {
+        fn value(&self, direction: Vec3) -> f64 {
+            SpherePdf.value(direction)
+        }
+
+        fn generate(&self) -> Vec3 {
+            let d = SpherePdf.generate();
+            Vec3::new(d.x, d.y, d.z.abs())
+        }
+    }
+
+    #[test]
+    fn test_histogram_catches_mismatch() {
+        assert!((integral(&Mislabelled) - 1.0).abs() < 0.01);
+        assert!(histogram_distance(&Mislabelled, 10_000) > 0.4);
+    }
+}
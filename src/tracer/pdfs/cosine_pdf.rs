@@ -0,0 +1,54 @@
+use std::f64::consts::PI;
+
+use crate::{random_cosine_direction, Onb, Pdf, Vec3};
+
+/// Cosine-weighted density over the hemisphere around a normal, which matches a
+/// Lambertian surface exactly.
+pub struct CosinePdf {
+    pub onb: Onb,
+}
+
+impl CosinePdf {
+    pub fn new(normal: Vec3) -> Self {
+        Self {
+            onb: Onb::new(normal),
+        }
+    }
+}
+
+impl Pdf for CosinePdf {
+    fn value(&self, direction: Vec3) -> f64 {
+        let cosine_theta = direction.unit().dot(self.onb.w);
+        (cosine_theta / PI).max(0.0)
+    }
+
+    fn generate(&self) -> Vec3 {
+        self.onb.transform(random_cosine_direction())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::tests::assert_consistent;
+
+    #[test]
+    fn test_consistent() {
+        for normal in [
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(1.0, -2.0, 0.5),
+            Vec3::new(0.0, 0.0, -3.0),
+        ] {
+            assert_consistent(&CosinePdf::new(normal));
+        }
+    }
+
+    #[test]
+    fn test_zero_below_the_surface() {
+        let pdf = CosinePdf::new(Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(pdf.value(Vec3::new(0.3, -1.0, 0.0)), 0.0);
+        for _ in 0..1000 {
+            assert!(pdf.value(pdf.generate()) > 0.0);
+        }
+    }
+}
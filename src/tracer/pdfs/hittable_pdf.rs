@@ -0,0 +1,67 @@
+use crate::{Hittable, Pdf, Point3, Vec3};
+
+/// Density of directions from `origin` towards an object, such as a light source.
+pub struct HittablePdf<'a> {
+    pub objects: &'a dyn Hittable,
+    pub origin: Point3,
+}
+
+impl<'a> HittablePdf<'a> {
+    pub fn new(objects: &'a dyn Hittable, origin: Point3) -> Self {
+        Self { objects, origin }
+    }
+}
+
+impl Pdf for HittablePdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        self.objects.pdf_value(self.origin, direction)
+    }
+
+    fn generate(&self) -> Vec3 {
+        self.objects.random(self.origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pdf::tests::assert_consistent, DiffuseLight, HittableList, Quad, Sphere};
+
+    fn light() -> DiffuseLight {
+        DiffuseLight::new(Vec3::ones())
+    }
+
+    #[test]
+    fn test_sphere_consistent() {
+        let sphere = Sphere::new(Vec3::new(0.0, 2.0, 0.0), 1.0, light());
+        assert_consistent(&HittablePdf::new(&sphere, Point3::zero()));
+    }
+
+    #[test]
+    fn test_quad_consistent() {
+        let quad = Quad::new(
+            Vec3::new(-1.0, -1.0, 1.5),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.5),
+            light(),
+        );
+        assert_consistent(&HittablePdf::new(&quad, Point3::zero()));
+    }
+
+    #[test]
+    fn test_list_consistent() {
+        let mut lights = HittableList::default();
+        lights.objects.push(Box::new(Sphere::new(
+            Vec3::new(-1.2, 0.5, -1.6),
+            0.8,
+            light(),
+        )));
+        lights.objects.push(Box::new(Quad::new(
+            Vec3::new(1.5, -1.0, -1.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            light(),
+        )));
+        assert_consistent(&HittablePdf::new(&lights, Point3::zero()));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{random_f64, Pdf, Vec3};
+
+/// Picks between two densities, the first with probability `weight`.
+pub struct MixturePdf<'a> {
+    pub pdfs: [&'a dyn Pdf; 2],
+    pub weight: f64,
+}
+
+impl<'a> MixturePdf<'a> {
+    pub fn new(p0: &'a dyn Pdf, p1: &'a dyn Pdf) -> Self {
+        Self::with_weight(p0, p1, 0.5)
+    }
+
+    pub fn with_weight(p0: &'a dyn Pdf, p1: &'a dyn Pdf, weight: f64) -> Self {
+        Self {
+            pdfs: [p0, p1],
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Pdf for MixturePdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        self.weight * self.pdfs[0].value(direction)
+            + (1.0 - self.weight) * self.pdfs[1].value(direction)
+    }
+
+    fn generate(&self) -> Vec3 {
+        if random_f64() < self.weight {
+            self.pdfs[0].generate()
+        } else {
+            self.pdfs[1].generate()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pdf::tests::assert_consistent, random_uint_vector, CosinePdf, SpherePdf};
+
+    #[test]
+    fn test_weighted_sum_of_parts() {
+        let cosine = CosinePdf::new(Vec3::new(0.0, 1.0, 0.0));
+        let mixture = MixturePdf::with_weight(&cosine, &SpherePdf, 0.3);
+        for _ in 0..100 {
+            let direction = random_uint_vector();
+            let expected = 0.3 * cosine.value(direction) + 0.7 * SpherePdf.value(direction);
+            assert!((mixture.value(direction) - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_consistent() {
+        let up = CosinePdf::new(Vec3::new(0.0, 1.0, 0.0));
+        let down = CosinePdf::new(Vec3::new(0.0, -1.0, 0.0));
+        for weight in [0.0, 0.3, 0.5, 1.0] {
+            assert_consistent(&MixturePdf::with_weight(&up, &SpherePdf, weight));
+        }
+        // Disjoint parts only agree with the histogram if each is picked as often as
+        // its weight says.
+        assert_consistent(&MixturePdf::with_weight(&up, &down, 0.2));
+    }
+}
@@ -0,0 +1,9 @@
+pub mod cosine_pdf;
+pub mod hittable_pdf;
+pub mod mixture_pdf;
+pub mod sphere_pdf;
+
+pub use cosine_pdf::*;
+pub use hittable_pdf::*;
+pub use mixture_pdf::*;
+pub use sphere_pdf::*;
@@ -0,0 +1,28 @@
+use std::f64::consts::PI;
+
+use crate::{random_uint_vector, Pdf, Vec3};
+
+/// Uniform density over all directions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpherePdf;
+
+impl Pdf for SpherePdf {
+    fn value(&self, _direction: Vec3) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn generate(&self) -> Vec3 {
+        random_uint_vector()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::tests::assert_consistent;
+
+    #[test]
+    fn test_consistent() {
+        assert_consistent(&SpherePdf);
+    }
+}
@@ -0,0 +1,14 @@
+use crate::Vec3;
+
+/// Angular distribution of light scattered inside a participating medium.
+///
+/// Directions are travel directions, so `cos_theta = 1` means the light keeps going
+/// straight ahead.
+pub trait PhaseFunction {
+    /// Density per steradian of scattering from `direction` into `scattered`.
+    fn p(&self, direction: Vec3, scattered: Vec3) -> f64;
+
+    /// Picks a new travel direction for light arriving along `direction`, distributed
+    /// exactly according to `p`.
+    fn sample(&self, direction: Vec3) -> Vec3;
+}
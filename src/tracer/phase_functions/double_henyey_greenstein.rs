@@ -0,0 +1,38 @@
+use crate::{henyey_greenstein, random_f64, sample_henyey_greenstein, PhaseFunction, Vec3};
+
+/// A blend of two Henyey-Greenstein lobes, usually one forward and one backward.
+///
+/// Real aerosols and cloud droplets have a strong forward peak plus a weaker
+/// back-scattering glow that a single lobe cannot reproduce.
+#[derive(Clone, Copy, Debug)]
+pub struct DoubleHenyeyGreenstein {
+    pub g_forward: f64,
+    pub g_backward: f64,
+    pub weight: f64, // Fraction of scattering in the forward lobe
+}
+
+impl DoubleHenyeyGreenstein {
+    pub fn new(g_forward: f64, g_backward: f64, weight: f64) -> Self {
+        Self {
+            g_forward: g_forward.clamp(-0.999, 0.999),
+            g_backward: g_backward.clamp(-0.999, 0.999),
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl PhaseFunction for DoubleHenyeyGreenstein {
+    fn p(&self, direction: Vec3, scattered: Vec3) -> f64 {
+        let cos_theta = direction.unit().dot(scattered.unit());
+        self.weight * henyey_greenstein(cos_theta, self.g_forward)
+            + (1.0 - self.weight) * henyey_greenstein(cos_theta, self.g_backward)
+    }
+
+    fn sample(&self, direction: Vec3) -> Vec3 {
+        if random_f64() < self.weight {
+            sample_henyey_greenstein(direction, self.g_forward)
+        } else {
+            sample_henyey_greenstein(direction, self.g_backward)
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::f64::consts::PI;
+
+use crate::{random_f64, Onb, PhaseFunction, Vec3};
+
+/// The Henyey-Greenstein phase function, a one-parameter fit to Mie scattering.
+///
+/// `g` is the mean cosine of the scattering angle: positive values scatter forward
+/// (fog, haze), negative values backward, and zero is isotropic.
+#[derive(Clone, Copy, Debug)]
+pub struct HenyeyGreenstein {
+    pub g: f64, // Asymmetry parameter in (-1, 1)
+}
+
+impl HenyeyGreenstein {
+    pub fn new(g: f64) -> Self {
+        Self {
+            g: g.clamp(-0.999, 0.999),
+        }
+    }
+}
+
+impl PhaseFunction for HenyeyGreenstein {
+    fn p(&self, direction: Vec3, scattered: Vec3) -> f64 {
+        henyey_greenstein(direction.unit().dot(scattered.unit()), self.g)
+    }
+
+    fn sample(&self, direction: Vec3) -> Vec3 {
+        sample_henyey_greenstein(direction, self.g)
+    }
+}
+
+/// Evaluates the Henyey-Greenstein density for the cosine of the scattering angle.
+pub fn henyey_greenstein(cos_theta: f64, g: f64) -> f64 {
+    let denom = 1.0 + g * g - 2.0 * g * cos_theta;
+    (1.0 - g * g) / (4.0 * PI * denom * denom.sqrt())
+}
+
+pub(crate) fn sample_henyey_greenstein(direction: Vec3, g: f64) -> Vec3 {
+    let u0 = random_f64();
+    let cos_theta = if g.abs() < 1e-3 {
+        1.0 - 2.0 * u0
+    } else {
+        // Inverted CDF of the distribution of cos(theta).
+        let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * u0);
+        ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+    };
+    direction_about(direction, cos_theta)
+}
+
+/// Builds a unit vector at angle acos(`cos_theta`) from `axis` with a uniformly random
+/// azimuth.
+pub(crate) fn direction_about(axis: Vec3, cos_theta: f64) -> Vec3 {
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let phi = 2.0 * PI * random_f64();
+    let local = Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+    Onb::new(axis).transform(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_henyey_greenstein_normalized() {
+        // Integrate over the sphere: 2*pi * integral of p over cos(theta) in [-1, 1].
+        for &g in &[-0.6, 0.0, 0.3, 0.85] {
+            let n = 20_000;
+            let integral: f64 = (0..n)
+                .map(|i| {
+                    let mu = -1.0 + 2.0 * (i as f64 + 0.5) / n as f64;
+                    henyey_greenstein(mu, g) * 2.0 / n as f64
+                })
+                .sum::<f64>()
+                * 2.0
+                * PI;
+            assert!((integral - 1.0).abs() < 1e-3, "g = {g}: {integral}");
+        }
+    }
+
+    #[test]
+    fn test_henyey_greenstein_mean_cosine() {
+        let phase = HenyeyGreenstein::new(0.7);
+        let direction = Vec3::new(0.0, 0.0, -2.0);
+        let n = 50_000;
+        let mean: f64 = (0..n)
+            .map(|_| phase.sample(direction).dot(direction.unit()))
+            .sum::<f64>()
+            / n as f64;
+        assert!((mean - 0.7).abs() < 0.02);
+    }
+}
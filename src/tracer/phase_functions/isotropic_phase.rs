@@ -0,0 +1,17 @@
+use std::f64::consts::PI;
+
+use crate::{random_uint_vector, PhaseFunction, Vec3};
+
+/// Scatters equally in every direction.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IsotropicPhase;
+
+impl PhaseFunction for IsotropicPhase {
+    fn p(&self, _direction: Vec3, _scattered: Vec3) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn sample(&self, _direction: Vec3) -> Vec3 {
+        random_uint_vector()
+    }
+}
@@ -0,0 +1,9 @@
+pub mod double_henyey_greenstein;
+pub mod henyey_greenstein;
+pub mod isotropic_phase;
+pub mod rayleigh;
+
+pub use double_henyey_greenstein::*;
+pub use henyey_greenstein::*;
+pub use isotropic_phase::*;
+pub use rayleigh::*;
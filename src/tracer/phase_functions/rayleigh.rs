@@ -0,0 +1,23 @@
+use std::f64::consts::PI;
+
+use crate::{direction_about, random_f64, PhaseFunction, Vec3};
+
+/// Scattering by particles much smaller than the wavelength, such as air molecules.
+/// Symmetric front to back, with a mild preference for both over sideways.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rayleigh;
+
+impl PhaseFunction for Rayleigh {
+    fn p(&self, direction: Vec3, scattered: Vec3) -> f64 {
+        let cos_theta = direction.unit().dot(scattered.unit());
+        3.0 / (16.0 * PI) * (1.0 + cos_theta * cos_theta)
+    }
+
+    fn sample(&self, direction: Vec3) -> Vec3 {
+        // The CDF of cos(theta) is (mu^3 + 3mu + 4) / 8; invert the cubic with Cardano.
+        let q = 8.0 * random_f64() - 4.0;
+        let root = (q * q / 4.0 + 1.0).sqrt();
+        let cos_theta = ((q / 2.0 + root).cbrt() + (q / 2.0 - root).cbrt()).clamp(-1.0, 1.0);
+        direction_about(direction, cos_theta)
+    }
+}
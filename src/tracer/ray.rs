@@ -1,16 +1,28 @@
-use super::vec3::Vec3;
+use super::vec3::{Point3, Vec3};
 
+#[derive(Clone, Copy, Debug)]
 pub struct Ray {
-    pub origin: Vec3,
+    pub origin: Point3,
     pub direction: Vec3,
+    pub time: f64,
+    pub wavelength: Option<f64>, // Wavelength in nm the path is restricted to, if any
 }
 
 impl Ray {
-    pub fn new(origin: Vec3, direction: Vec3) -> Self {
-        Self { origin, direction }
+    pub fn new(origin: Point3, direction: Vec3) -> Self {
+        Self::with_time(origin, direction, 0.0)
     }
 
-    pub fn at(&self, t: f64) -> Vec3 {
+    pub fn with_time(origin: Point3, direction: Vec3, time: f64) -> Self {
+        Self {
+            origin,
+            direction,
+            time,
+            wavelength: None,
+        }
+    }
+
+    pub fn at(&self, t: f64) -> Point3 {
         self.origin + self.direction * t
     }
 }
@@ -0,0 +1,358 @@
+use std::sync::OnceLock;
+
+use crate::{
+    cie_xyz, xyz_to_linear_srgb, Color, Mat3, SampledSpectrum, SampledWavelengths, Vec3,
+    LAMBDA_MAX, LAMBDA_MIN,
+};
+
+/// CIE standard illuminant D65, relative spectral power from 360nm to 830nm in 10nm steps.
+const D65: [f64; 48] = [
+    46.6383, 52.0891, 49.9755, 54.6482, 82.7549, 91.486, 93.4318, 86.6823, 104.865, 117.008,
+    117.812, 114.861, 115.923, 108.811, 109.354, 107.802, 104.79, 107.689, 104.405, 104.046, 100.0,
+    96.3342, 95.788, 88.6856, 90.0062, 89.5991, 87.6987, 83.2886, 83.6992, 80.0268, 80.2146,
+    82.2778, 78.2842, 69.7213, 71.6091, 74.349, 61.604, 69.8856, 75.087, 63.5927, 46.4182, 66.8054,
+    63.3828, 64.304, 59.4519, 51.959, 57.4406, 60.3125,
+];
+
+/// D65 at `lambda` nanometers, scaled so that it has unit luminance. This is the
+/// spectrum of RGB white light.
+pub fn d65(lambda: f64) -> f64 {
+    static NORMALIZATION: OnceLock<f64> = OnceLock::new();
+    let normalization = *NORMALIZATION.get_or_init(|| {
+        let steps = 470;
+        let d_lambda = (LAMBDA_MAX - LAMBDA_MIN) / steps as f64;
+        (0..steps)
+            .map(|i| {
+                let lambda = LAMBDA_MIN + (i as f64 + 0.5) * d_lambda;
+                cie_xyz(lambda).y * d65_table(lambda) * d_lambda
+            })
+            .sum::<f64>()
+    });
+    d65_table(lambda) / normalization
+}
+
+fn d65_table(lambda: f64) -> f64 {
+    let x = ((lambda - 360.0) / 10.0).clamp(0.0, (D65.len() - 1) as f64);
+    let i = (x as usize).min(D65.len() - 2);
+    let t = x - i as f64;
+    D65[i] * (1.0 - t) + D65[i + 1] * t
+}
+
+/// A smooth reflectance spectrum `s(x)` with `x = c0 t^2 + c1 t + c2`, where `t` maps
+/// the visible range to [0, 1] and `s(x) = 1/2 + x / (2 sqrt(1 + x^2))`.
+///
+/// This is the representation of Jakob and Hanika, "A Low-Dimensional Function Space
+/// for Efficient Spectral Upsampling" (2019): three coefficients cover every color in
+/// gamut and the result is always a valid reflectance in [0, 1].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RgbSigmoid {
+    pub c: [f64; 3],
+}
+
+impl RgbSigmoid {
+    /// The spectrum whose linear sRGB color under D65 is `rgb`, with components clamped
+    /// to [0, 1]. Grays are exact; other colors are interpolated from a table of fits
+    /// built on first use, so no fitting happens while rendering.
+    pub fn from_rgb(rgb: Color) -> Self {
+        let rgb = Color::new(
+            rgb.x.clamp(0.0, 1.0),
+            rgb.y.clamp(0.0, 1.0),
+            rgb.z.clamp(0.0, 1.0),
+        );
+        if rgb.x == rgb.y && rgb.y == rgb.z {
+            return Self::fit(rgb);
+        }
+        coefficient_table().lookup(rgb)
+    }
+
+    pub fn eval(&self, lambda: f64) -> f64 {
+        let t = (lambda - LAMBDA_MIN) / (LAMBDA_MAX - LAMBDA_MIN);
+        sigmoid(self.c[0] * t * t + self.c[1] * t + self.c[2])
+    }
+
+    /// The spectrum whose color is `target`, which must lie in [0, 1].
+    fn fit(target: Color) -> Self {
+        // Grays are flat spectra and can be inverted directly.
+        if target.x == target.y && target.y == target.z {
+            let v = target.x;
+            let c2 = if v <= 0.0 {
+                f64::NEG_INFINITY
+            } else if v >= 1.0 {
+                f64::INFINITY
+            } else {
+                (v - 0.5) / (v * (1.0 - v)).sqrt()
+            };
+            return Self { c: [0.0, 0.0, c2] };
+        }
+        Self::solve(target)
+    }
+
+    /// Minimizes the RGB error of the spectrum with damped Gauss-Newton
+    /// (Levenberg-Marquardt) iterations.
+    fn solve(target: Color) -> Self {
+        let mut c = [0.0; 3];
+        let (mut rgb, mut jacobian) = Self { c }.color_and_jacobian();
+        let mut error = (rgb - target).length_squard();
+        let mut damping = 1e-3;
+        for _ in 0..200 {
+            if error < 1e-12 {
+                break;
+            }
+            let jt = jacobian.transpose();
+            let mut normal = jt * jacobian;
+            for i in 0..3 {
+                normal.m[i][i] += damping * (1.0 + normal.m[i][i]);
+            }
+            let Some(inverse) = normal.inverse() else {
+                damping *= 10.0;
+                continue;
+            };
+            let step = inverse * (jt * (target - rgb));
+            let candidate = [c[0] + step.x, c[1] + step.y, c[2] + step.z];
+            let (candidate_rgb, candidate_jacobian) = Self { c: candidate }.color_and_jacobian();
+            let candidate_error = (candidate_rgb - target).length_squard();
+            if candidate_error < error {
+                // Targets outside the gamut are only approached as the coefficients run
+                // off to infinity; stop once that stalls.
+                let stalled = candidate_error > error * (1.0 - 1e-6);
+                c = candidate;
+                rgb = candidate_rgb;
+                jacobian = candidate_jacobian;
+                error = candidate_error;
+                damping = (damping * 0.3).max(1e-9);
+                if stalled {
+                    break;
+                }
+            } else {
+                damping *= 10.0;
+                if damping > 1e9 {
+                    break;
+                }
+            }
+        }
+        Self { c }
+    }
+
+    /// Linear sRGB color of the spectrum under D65 and its derivative with respect to
+    /// the coefficients (one column per coefficient).
+    fn color_and_jacobian(&self) -> (Color, Mat3) {
+        let mut rgb = Color::zero();
+        let mut columns = [Vec3::zero(); 3];
+        for &(t, weight) in color_weights() {
+            let x = self.c[0] * t * t + self.c[1] * t + self.c[2];
+            let r = 1.0 + x * x;
+            let root = r.sqrt();
+            rgb += weight * sigmoid(x);
+            let ds = 0.5 / (r * root);
+            columns[0] += weight * (ds * t * t);
+            columns[1] += weight * (ds * t);
+            columns[2] += weight * ds;
+        }
+        (rgb, Mat3::from_columns(columns[0], columns[1], columns[2]))
+    }
+}
+
+/// Number of table entries along each axis of `CoefficientTable`.
+const TABLE_RESOLUTION: usize = 16;
+
+/// Precomputed fits over the RGB cube, after Jakob and Hanika's tables (as used by
+/// PBRT's RGBToSpectrumTable). Colors are addressed by their largest component `z` and
+/// the other two divided by it, each in [0, 1], with one block of entries per choice of
+/// largest component. The `z` entries are packed towards black and white, where the
+/// coefficients change fastest.
+struct CoefficientTable {
+    z_nodes: [f64; TABLE_RESOLUTION],
+    coefficients: Vec<[f64; 3]>,
+}
+
+impl CoefficientTable {
+    fn build() -> Self {
+        let n = TABLE_RESOLUTION;
+        let smoothstep = |x: f64| x * x * (3.0 - 2.0 * x);
+        let z_nodes = std::array::from_fn(|k| smoothstep(smoothstep(k as f64 / (n - 1) as f64)));
+        let mut coefficients = vec![[0.0; 3]; 3 * n * n * n];
+
+        for l in 0..3 {
+            for k in 0..n {
+                for j in 0..n {
+                    for i in 0..n {
+                        let z = z_nodes[k];
+                        let mut rgb = Color::zero();
+                        rgb[l] = z;
+                        rgb[(l + 1) % 3] = i as f64 / (n - 1) as f64 * z;
+                        rgb[(l + 2) % 3] = j as f64 / (n - 1) as f64 * z;
+                        // Fitting even the grays keeps the coefficients finite, so
+                        // entries can always be blended.
+                        coefficients[((l * n + k) * n + j) * n + i] = RgbSigmoid::solve(rgb).c;
+                    }
+                }
+            }
+        }
+        Self {
+            z_nodes,
+            coefficients,
+        }
+    }
+
+    /// Trilinearly interpolated coefficients for `rgb`, which must lie in [0, 1] and
+    /// not be black.
+    fn lookup(&self, rgb: Color) -> RgbSigmoid {
+        let n = TABLE_RESOLUTION;
+        let l = if rgb.x >= rgb.y && rgb.x >= rgb.z {
+            0
+        } else if rgb.y >= rgb.z {
+            1
+        } else {
+            2
+        };
+        let z = rgb[l];
+        let x = rgb[(l + 1) % 3] / z * (n - 1) as f64;
+        let y = rgb[(l + 2) % 3] / z * (n - 1) as f64;
+
+        let cell = |v: f64| (v as usize).min(n - 2);
+        let (i, j) = (cell(x), cell(y));
+        let k = (self.z_nodes.partition_point(|&node| node <= z).max(1) - 1).min(n - 2);
+        let (dx, dy) = (x - i as f64, y - j as f64);
+        let dz = (z - self.z_nodes[k]) / (self.z_nodes[k + 1] - self.z_nodes[k]);
+
+        let mut c = [0.0; 3];
+        for (k, wz) in [(k, 1.0 - dz), (k + 1, dz)] {
+            for (j, wy) in [(j, 1.0 - dy), (j + 1, dy)] {
+                for (i, wx) in [(i, 1.0 - dx), (i + 1, dx)] {
+                    let entry = self.coefficients[((l * n + k) * n + j) * n + i];
+                    for (c, entry) in c.iter_mut().zip(entry) {
+                        *c += wx * wy * wz * entry;
+                    }
+                }
+            }
+        }
+        RgbSigmoid { c }
+    }
+}
+
+fn coefficient_table() -> &'static CoefficientTable {
+    static TABLE: OnceLock<CoefficientTable> = OnceLock::new();
+    TABLE.get_or_init(CoefficientTable::build)
+}
+
+fn sigmoid(x: f64) -> f64 {
+    if x.is_infinite() {
+        return if x > 0.0 { 1.0 } else { 0.0 };
+    }
+    0.5 + x / (2.0 * (1.0 + x * x).sqrt())
+}
+
+/// Quadrature table turning a reflectance spectrum into linear sRGB under D65: pairs of
+/// normalized wavelength and the color contributed per unit reflectance.
+fn color_weights() -> &'static [(f64, Color)] {
+    static WEIGHTS: OnceLock<Vec<(f64, Color)>> = OnceLock::new();
+    WEIGHTS.get_or_init(|| {
+        let steps = 47;
+        let d_lambda = (LAMBDA_MAX - LAMBDA_MIN) / steps as f64;
+        (0..steps)
+            .map(|i| {
+                let t = (i as f64 + 0.5) / steps as f64;
+                let lambda = LAMBDA_MIN + t * (LAMBDA_MAX - LAMBDA_MIN);
+                let rgb = xyz_to_linear_srgb(cie_xyz(lambda) * (d65(lambda) * d_lambda));
+                (t, rgb)
+            })
+            .collect()
+    })
+}
+
+/// Upsamples a reflectance or transmittance color to the path's wavelengths.
+pub fn rgb_to_reflectance(rgb: Color, wavelengths: &SampledWavelengths) -> SampledSpectrum {
+    let fit = RgbSigmoid::from_rgb(rgb);
+    SampledSpectrum::from_fn(wavelengths, |lambda| fit.eval(lambda))
+}
+
+/// Upsamples a color that may exceed one, such as an absorption coefficient, to the
+/// path's wavelengths.
+pub fn rgb_to_unbounded(rgb: Color, wavelengths: &SampledWavelengths) -> SampledSpectrum {
+    let max = rgb.x.max(rgb.y).max(rgb.z);
+    if max <= 0.0 {
+        return SampledSpectrum::zero();
+    }
+    // Fit the color scaled into the middle of the reflectance range, then scale back.
+    let scale = 2.0 * max;
+    let fit = RgbSigmoid::from_rgb(rgb / scale);
+    SampledSpectrum::from_fn(wavelengths, |lambda| scale * fit.eval(lambda))
+}
+
+/// Upsamples an emitted radiance color, which may exceed one, to the path's wavelengths.
+/// RGB white becomes D65 with unit luminance.
+pub fn rgb_to_illuminant(rgb: Color, wavelengths: &SampledWavelengths) -> SampledSpectrum {
+    rgb_to_unbounded(rgb, wavelengths) * SampledSpectrum::from_fn(wavelengths, d65)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random_f64;
+
+    fn fitted_color(fit: &RgbSigmoid) -> Color {
+        fit.color_and_jacobian().0
+    }
+
+    #[test]
+    fn test_d65_white_point() {
+        let mut xyz = Vec3::zero();
+        for i in 0..470 {
+            let lambda = LAMBDA_MIN + i as f64 + 0.5;
+            xyz += cie_xyz(lambda) * d65(lambda);
+        }
+        let sum = xyz.x + xyz.y + xyz.z;
+        assert!((xyz.y - 1.0).abs() < 1e-3);
+        assert!((xyz.x / sum - 0.3127).abs() < 0.005);
+        assert!((xyz.y / sum - 0.3290).abs() < 0.005);
+    }
+
+    #[test]
+    fn test_fit_round_trip() {
+        for rgb in [
+            Color::new(0.8, 0.3, 0.1),
+            Color::new(0.1, 0.6, 0.2),
+            Color::new(0.2, 0.3, 0.9),
+            Color::new(0.5, 0.5, 0.5),
+            Color::ones(),
+        ] {
+            let fit = RgbSigmoid::from_rgb(rgb);
+            assert!((fitted_color(&fit) - rgb).length() < 0.02, "{rgb:?}");
+        }
+    }
+
+    #[test]
+    fn test_fit_is_a_reflectance() {
+        let fit = RgbSigmoid::from_rgb(Color::new(1.0, 0.0, 0.0));
+        for i in 0..=10 {
+            let lambda = LAMBDA_MIN + (LAMBDA_MAX - LAMBDA_MIN) * i as f64 / 10.0;
+            let s = fit.eval(lambda);
+            assert!((0.0..=1.0).contains(&s));
+        }
+        assert!(fit.eval(650.0) > fit.eval(450.0));
+    }
+
+    #[test]
+    fn test_table_tracks_direct_fits() {
+        let mut total = 0.0;
+        let n = 200;
+        for _ in 0..n {
+            let rgb = Color::new(random_f64(), random_f64(), random_f64());
+            let direct = fitted_color(&RgbSigmoid::fit(rgb));
+            let error = (fitted_color(&RgbSigmoid::from_rgb(rgb)) - direct).length();
+            assert!(error < 0.15, "{rgb:?}");
+            total += error;
+        }
+        assert!(total / (n as f64) < 0.01);
+    }
+
+    #[test]
+    fn test_unbounded_gray_is_flat() {
+        let wavelengths = SampledWavelengths::sample_uniform(0.3);
+        let s = rgb_to_unbounded(Color::new(2.5, 2.5, 2.5), &wavelengths);
+        for v in s.0 {
+            assert!((v - 2.5).abs() < 1e-9);
+        }
+        assert_eq!(rgb_to_unbounded(Color::zero(), &wavelengths).0, [0.0; 4]);
+    }
+}
@@ -0,0 +1,213 @@
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign};
+
+use crate::{cie_xyz, Vec3, LAMBDA_MAX, LAMBDA_MIN};
+
+/// Number of wavelengths carried by each spectral path.
+pub const SPECTRUM_SAMPLES: usize = 4;
+
+/// The wavelengths a spectral path is evaluated at.
+///
+/// The first one is the hero wavelength; the others are evenly spaced rotations of it
+/// across the visible range, so a single random number covers the spectrum well.
+#[derive(Clone, Copy, Debug)]
+pub struct SampledWavelengths {
+    pub lambda: [f64; SPECTRUM_SAMPLES],
+    pub pdf: [f64; SPECTRUM_SAMPLES],
+}
+
+impl SampledWavelengths {
+    pub fn sample_uniform(u: f64) -> Self {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let mut lambda = [0.0; SPECTRUM_SAMPLES];
+        for (i, l) in lambda.iter_mut().enumerate() {
+            let offset = (u + i as f64 / SPECTRUM_SAMPLES as f64).fract();
+            *l = LAMBDA_MIN + offset * range;
+        }
+        Self {
+            lambda,
+            pdf: [1.0 / range; SPECTRUM_SAMPLES],
+        }
+    }
+
+    pub fn hero(&self) -> f64 {
+        self.lambda[0]
+    }
+
+    /// Drops every wavelength but the hero, after an interaction (such as dispersive
+    /// refraction) whose outgoing direction only holds for that one wavelength.
+    pub fn terminate_secondary(&mut self) {
+        if self.secondary_terminated() {
+            return;
+        }
+        for pdf in self.pdf.iter_mut().skip(1) {
+            *pdf = 0.0;
+        }
+        self.pdf[0] /= SPECTRUM_SAMPLES as f64;
+    }
+
+    pub fn secondary_terminated(&self) -> bool {
+        self.pdf[1..].iter().all(|&pdf| pdf == 0.0)
+    }
+
+    /// Monte Carlo estimate of the CIE XYZ color of spectral radiance `l`.
+    pub fn to_xyz(&self, l: &SampledSpectrum) -> Vec3 {
+        let mut xyz = Vec3::zero();
+        for i in 0..SPECTRUM_SAMPLES {
+            if self.pdf[i] > 0.0 {
+                xyz += cie_xyz(self.lambda[i]) * (l.0[i] / self.pdf[i]);
+            }
+        }
+        xyz / SPECTRUM_SAMPLES as f64
+    }
+}
+
+/// A spectral quantity evaluated at each of a path's `SampledWavelengths`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SampledSpectrum(pub [f64; SPECTRUM_SAMPLES]);
+
+impl SampledSpectrum {
+    pub fn splat(value: f64) -> Self {
+        Self([value; SPECTRUM_SAMPLES])
+    }
+
+    pub fn zero() -> Self {
+        Self::splat(0.0)
+    }
+
+    pub fn ones() -> Self {
+        Self::splat(1.0)
+    }
+
+    /// Evaluates `f` at each of the sampled wavelengths.
+    pub fn from_fn(wavelengths: &SampledWavelengths, f: impl Fn(f64) -> f64) -> Self {
+        Self(wavelengths.lambda.map(f))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&v| v == 0.0)
+    }
+
+    pub fn max_value(&self) -> f64 {
+        self.0.iter().copied().fold(0.0, f64::max)
+    }
+}
+
+impl Add for SampledSpectrum {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl AddAssign for SampledSpectrum {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Mul for SampledSpectrum {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] * rhs.0[i]))
+    }
+}
+
+impl MulAssign for SampledSpectrum {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul<f64> for SampledSpectrum {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Self(self.0.map(|v| v * rhs))
+    }
+}
+
+impl Div<f64> for SampledSpectrum {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self {
+        Self(self.0.map(|v| v / rhs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANGE: f64 = LAMBDA_MAX - LAMBDA_MIN;
+
+    #[test]
+    fn test_sample_uniform() {
+        let wavelengths = SampledWavelengths::sample_uniform(0.0);
+        assert_eq!(wavelengths.lambda, [360.0, 477.5, 595.0, 712.5]);
+        assert_eq!(wavelengths.hero(), 360.0);
+        assert!(wavelengths.pdf.iter().all(|&pdf| pdf == 1.0 / RANGE));
+        assert!(!wavelengths.secondary_terminated());
+    }
+
+    #[test]
+    fn test_terminate_secondary() {
+        let mut wavelengths = SampledWavelengths::sample_uniform(0.3);
+        wavelengths.terminate_secondary();
+        assert!(wavelengths.secondary_terminated());
+        // The hero now stands in for all four samples.
+        assert_eq!(wavelengths.pdf, [1.0 / (RANGE * 4.0), 0.0, 0.0, 0.0]);
+
+        // Terminating again must not rescale the hero a second time.
+        wavelengths.terminate_secondary();
+        assert_eq!(wavelengths.pdf[0], 1.0 / (RANGE * 4.0));
+
+        let xyz = wavelengths.to_xyz(&SampledSpectrum::ones());
+        let expected = cie_xyz(wavelengths.hero()) * RANGE;
+        assert!((xyz - expected).length() < 1e-9);
+    }
+
+    #[test]
+    fn test_to_xyz_of_unit_spectrum() {
+        // A single set of wavelengths gives the four-point estimate of the integral.
+        let wavelengths = SampledWavelengths::sample_uniform(0.0);
+        let y = wavelengths.to_xyz(&SampledSpectrum::ones()).y;
+        let expected: f64 = wavelengths.lambda.iter().map(|&l| cie_xyz(l).y).sum();
+        assert!((y - expected * RANGE / 4.0).abs() < 1e-9);
+
+        // Averaged over many, it converges to the integral of y-bar, about 106.86.
+        let n = 1000;
+        let mean_y = (0..n)
+            .map(|i| {
+                let wavelengths = SampledWavelengths::sample_uniform((i as f64 + 0.5) / n as f64);
+                wavelengths.to_xyz(&SampledSpectrum::ones()).y
+            })
+            .sum::<f64>()
+            / n as f64;
+        assert!((mean_y - 106.86).abs() < 1.0, "{mean_y}");
+    }
+
+    #[test]
+    fn test_max_value() {
+        assert_eq!(SampledSpectrum([0.5, 3.0, -1.0, 2.0]).max_value(), 3.0);
+        assert_eq!(SampledSpectrum::zero().max_value(), 0.0);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = SampledSpectrum([1.0, 2.0, 3.0, 4.0]);
+        let b = SampledSpectrum::splat(2.0);
+        assert_eq!(a + b, SampledSpectrum([3.0, 4.0, 5.0, 6.0]));
+        assert_eq!(a * b, SampledSpectrum([2.0, 4.0, 6.0, 8.0]));
+        assert_eq!(a * 0.5, SampledSpectrum([0.5, 1.0, 1.5, 2.0]));
+        assert_eq!(a / 2.0, a * 0.5);
+
+        let mut c = a;
+        c += b;
+        c *= b;
+        assert_eq!(c, SampledSpectrum([6.0, 8.0, 10.0, 12.0]));
+        assert!(SampledSpectrum::zero().is_zero());
+        assert!(!c.is_zero());
+    }
+}
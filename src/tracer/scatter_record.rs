@@ -1,6 +1,20 @@
 use crate::{Ray, Vec3};
 
 pub struct ScatterRecord {
-    pub attenuation: Vec3,
+    pub attenuation: Vec3, // Path weight for following scatter_ray: eval / pdf, or the specular tint
     pub scatter_ray: Ray,
+    pub pdf: f64, // Solid-angle density of the sampled direction; 0 when specular
+    pub is_specular: bool, // The direction came from a delta distribution that cannot be evaluated
+}
+
+impl ScatterRecord {
+    /// A perfectly specular (delta) scattering event such as mirror reflection or refraction.
+    pub fn specular(attenuation: Vec3, scatter_ray: Ray) -> Self {
+        Self {
+            attenuation,
+            scatter_ray,
+            pdf: 0.0,
+            is_specular: true,
+        }
+    }
 }
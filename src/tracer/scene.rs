@@ -0,0 +1,197 @@
+use crate::{
+    random_f64, Color, DeltaLight, Environment, GradientSky, HitRecord, Hittable, HittableList,
+    Interval, Point3, Ray, Vec3,
+};
+
+pub struct Scene {
+    pub world: HittableList,
+    pub environment: Box<dyn Environment>, // Radiance for rays that escape the world
+    pub lights: Vec<usize>, // Indices of emissive objects in world that can be sampled
+    pub delta_lights: Vec<DeltaLight>, // Point and directional lights
+}
+
+impl Scene {
+    /// Creates a scene, collecting every emissive object in `world` into the light list.
+    /// Lights nested in lists and static transforms are found too, and sampled through
+    /// their top-level object. Objects added to `world` later are not picked up
+    /// automatically.
+    pub fn new(world: HittableList, environment: Box<dyn Environment>) -> Self {
+        let lights = world
+            .objects
+            .iter()
+            .enumerate()
+            .filter(|(_, object)| object.is_emissive())
+            .map(|(index, _)| index)
+            .collect();
+        Self {
+            world,
+            environment,
+            lights,
+            delta_lights: Vec::new(),
+        }
+    }
+
+    pub fn with_delta_light(mut self, light: DeltaLight) -> Self {
+        self.delta_lights.push(light);
+        self
+    }
+
+    /// Light reflected towards `ray` at `hit_record` from every delta light, with hard
+    /// shadows.
+    pub fn delta_lighting(&self, ray: &Ray, hit_record: &HitRecord) -> Color {
+        let mut color = Color::zero();
+        for light in &self.delta_lights {
+            let (direction, distance, irradiance) = light.illuminate(hit_record.p);
+            let bsdf = hit_record.material.eval(ray, hit_record, direction);
+            if bsdf.near_zero() {
+                continue;
+            }
+
+            let mut shadow_ray = Ray::with_time(hit_record.p, direction, ray.time);
+            shadow_ray.wavelength = ray.wavelength;
+            let visibility = self
+                .world
+                .transmittance(&shadow_ray, Interval::new(0.001, distance - 0.001));
+            color += visibility * bsdf * irradiance;
+        }
+        color
+    }
+
+    /// Picks a light uniformly and returns a direction from `origin` towards it.
+    pub fn sample_light(&self, origin: Point3) -> Option<Vec3> {
+        if self.lights.is_empty() {
+            return None;
+        }
+        let choice =
+            ((random_f64() * self.lights.len() as f64) as usize).min(self.lights.len() - 1);
+        Some(self.world.objects[self.lights[choice]].random(origin))
+    }
+
+    /// Solid-angle density with which `sample_light` produces `direction` from `origin`.
+    pub fn light_pdf(&self, origin: Point3, direction: Vec3) -> f64 {
+        if self.lights.is_empty() {
+            return 0.0;
+        }
+        let weight = 1.0 / self.lights.len() as f64;
+        self.lights
+            .iter()
+            .map(|&index| weight * self.world.objects[index].pdf_value(origin, direction))
+            .sum()
+    }
+
+    /// The closest light along `ray`, ignoring all other objects.
+    pub fn hit_light(&self, ray: &Ray, ray_t: Interval) -> Option<HitRecord<'_>> {
+        let mut closest_so_far = ray_t.max;
+        let mut res = None;
+        for &index in &self.lights {
+            let object = &self.world.objects[index];
+            if let Some(hr) = object.hit(ray, Interval::new(ray_t.min, closest_so_far)) {
+                closest_so_far = hr.t;
+                res = Some(hr);
+            }
+        }
+        res
+    }
+
+    /// Whether `hit_record`, found by tracing `ray` through the world, lies on a light
+    /// in the light list. Non-emissive surfaces grouped with a light do not count.
+    pub fn is_light_hit(&self, ray: &Ray, hit_record: &HitRecord) -> bool {
+        hit_record.material.is_emissive()
+            && self
+                .hit_light(ray, Interval::new(0.001, f64::INFINITY))
+                .is_some_and(|light| (light.t - hit_record.t).abs() <= 1e-9 * hit_record.t.max(1.0))
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new(HittableList::default(), Box::new(GradientSky::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{DiffuseLight, Lambertian, Mat4, Quad, Transform};
+
+    /// A 2x2 light facing down from y = 2, moved there by a transform and grouped with a
+    /// dull panel beside it.
+    fn nested_light_scene() -> Scene {
+        let light: Arc<dyn Hittable> = Arc::new(Quad::new(
+            Vec3::new(-1.0, 0.0, -1.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            DiffuseLight::new(Color::ones()),
+        ));
+        let mut group = HittableList::default();
+        group.objects.push(Box::new(Transform::new(
+            light,
+            Mat4::translation(Vec3::new(0.0, 2.0, 0.0)),
+        )));
+        group.objects.push(Box::new(Quad::new(
+            Vec3::new(3.0, 2.0, -1.0),
+            Vec3::new(0.0, 0.0, 2.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Lambertian::new(Color::ones()),
+        )));
+
+        let mut world = HittableList::default();
+        world.objects.push(Box::new(group));
+        world.objects.push(Box::new(Quad::new(
+            Vec3::new(-5.0, 0.0, -5.0),
+            Vec3::new(10.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 10.0),
+            Lambertian::new(Color::ones()),
+        )));
+        Scene::new(world, Box::new(GradientSky::default()))
+    }
+
+    #[test]
+    fn test_nested_lights_are_collected() {
+        assert_eq!(nested_light_scene().lights, vec![0]);
+    }
+
+    #[test]
+    fn test_light_pdf() {
+        let scene = nested_light_scene();
+        // Straight up, the light is 2 units away and faces the point head on.
+        let pdf = scene.light_pdf(Vec3::zero(), Vec3::new(0.0, 1.0, 0.0));
+        assert!((pdf - 4.0 / 4.0).abs() < 1e-9);
+        // The dull panel grouped with the light is not sampled.
+        let towards_panel = Vec3::new(4.0, 2.0, 0.0);
+        assert_eq!(scene.light_pdf(Vec3::zero(), towards_panel), 0.0);
+    }
+
+    #[test]
+    fn test_sample_light() {
+        let scene = nested_light_scene();
+        for _ in 0..100 {
+            let direction = scene.sample_light(Vec3::zero()).unwrap();
+            let ray = Ray::new(Vec3::zero(), direction);
+            let hr = scene
+                .world
+                .hit(&ray, Interval::new(0.001, f64::INFINITY))
+                .unwrap();
+            assert!(hr.material.is_emissive());
+            assert!(scene.light_pdf(Vec3::zero(), direction) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_is_light_hit() {
+        let scene = nested_light_scene();
+        let trace = |direction: Vec3| {
+            let ray = Ray::new(Vec3::new(0.0, 0.5, 0.0), direction);
+            let hr = scene
+                .world
+                .hit(&ray, Interval::new(0.001, f64::INFINITY))
+                .unwrap();
+            scene.is_light_hit(&ray, &hr)
+        };
+        assert!(trace(Vec3::new(0.0, 1.0, 0.0)));
+        assert!(!trace(Vec3::new(4.0, 1.5, 0.0)));
+        assert!(!trace(Vec3::new(0.0, -1.0, 0.0)));
+    }
+}
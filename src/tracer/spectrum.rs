@@ -0,0 +1,135 @@
+use std::sync::OnceLock;
+
+use crate::{xyz_to_linear_srgb, Color, Vec3};
+
+/// Visible range used when integrating spectra, in nanometers.
+pub const LAMBDA_MIN: f64 = 360.0;
+pub const LAMBDA_MAX: f64 = 830.0;
+
+/// CIE 1931 2-degree color matching functions at `lambda` nanometers, using the
+/// multi-lobe Gaussian fit of Wyman, Sloan and Shirley (2013).
+pub fn cie_xyz(lambda: f64) -> Vec3 {
+    let g = |x: f64, mu: f64, sigma1: f64, sigma2: f64| {
+        let t = (x - mu) / if x < mu { sigma1 } else { sigma2 };
+        (-0.5 * t * t).exp()
+    };
+    Vec3::new(
+        1.056 * g(lambda, 599.8, 37.9, 31.0) + 0.362 * g(lambda, 442.0, 16.0, 26.7)
+            - 0.065 * g(lambda, 501.1, 20.4, 26.2),
+        0.821 * g(lambda, 568.8, 46.9, 40.5) + 0.286 * g(lambda, 530.9, 16.3, 31.1),
+        1.217 * g(lambda, 437.0, 11.8, 36.0) + 0.681 * g(lambda, 459.0, 26.0, 13.8),
+    )
+}
+
+/// Spectral radiance of a black body (Planck's law) at `lambda` nanometers, in
+/// W / (m^2 sr nm).
+pub fn planck(lambda: f64, temperature: f64) -> f64 {
+    if temperature <= 0.0 {
+        return 0.0;
+    }
+    const C: f64 = 299_792_458.0;
+    const H: f64 = 6.626_070_15e-34;
+    const KB: f64 = 1.380_649e-23;
+    let l = lambda * 1e-9;
+    let le = 2.0 * H * C * C / (l.powi(5) * ((H * C / (l * KB * temperature)).exp() - 1.0));
+    le * 1e-9
+}
+
+/// Luminance (CIE Y) of a black body's spectral radiance at `temperature` Kelvin, used to
+/// normalize black-body emitters.
+pub fn blackbody_luminance(temperature: f64) -> f64 {
+    let steps = 94;
+    let d_lambda = (LAMBDA_MAX - LAMBDA_MIN) / steps as f64;
+    (0..steps)
+        .map(|i| {
+            let lambda = LAMBDA_MIN + (i as f64 + 0.5) * d_lambda;
+            cie_xyz(lambda).y * planck(lambda, temperature) * d_lambda
+        })
+        .sum()
+}
+
+/// Linear sRGB color of a black body at `temperature` Kelvin, normalized to unit luminance.
+pub fn blackbody_color(temperature: f64) -> Color {
+    if temperature <= 0.0 {
+        return Color::zero();
+    }
+    let steps = 94;
+    let d_lambda = (LAMBDA_MAX - LAMBDA_MIN) / steps as f64;
+    let mut xyz = Vec3::zero();
+    for i in 0..steps {
+        let lambda = LAMBDA_MIN + (i as f64 + 0.5) * d_lambda;
+        xyz += cie_xyz(lambda) * planck(lambda, temperature);
+    }
+    if xyz.y <= 0.0 {
+        return Color::zero();
+    }
+    let rgb = xyz_to_linear_srgb(xyz / xyz.y);
+    Color::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+}
+
+/// Maps a uniform sample `u` to a wavelength in the visible range, together with the
+/// RGB weight a path carrying only that wavelength should be multiplied by.
+///
+/// The weights average to white over the range, so a path that splits white light into
+/// a single wavelength keeps its expected color.
+pub fn sample_visible_wavelength(u: f64) -> (f64, Color) {
+    let lambda = LAMBDA_MIN + u * (LAMBDA_MAX - LAMBDA_MIN);
+    let mean = wavelength_rgb_mean();
+    let rgb = wavelength_rgb(lambda);
+    (
+        lambda,
+        Color::new(rgb.x / mean.x, rgb.y / mean.y, rgb.z / mean.z),
+    )
+}
+
+/// Linear sRGB color of a single wavelength, with out-of-gamut negatives clipped.
+fn wavelength_rgb(lambda: f64) -> Color {
+    let rgb = xyz_to_linear_srgb(cie_xyz(lambda));
+    Color::new(rgb.x.max(0.0), rgb.y.max(0.0), rgb.z.max(0.0))
+}
+
+/// Average of `wavelength_rgb` over the visible range, computed once.
+fn wavelength_rgb_mean() -> Color {
+    static MEAN: OnceLock<Color> = OnceLock::new();
+    *MEAN.get_or_init(|| {
+        let steps = 1000;
+        let d_lambda = (LAMBDA_MAX - LAMBDA_MIN) / steps as f64;
+        let mut sum = Color::zero();
+        for i in 0..steps {
+            sum += wavelength_rgb(LAMBDA_MIN + (i as f64 + 0.5) * d_lambda);
+        }
+        sum / steps as f64
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blackbody_color() {
+        let warm = blackbody_color(1800.0);
+        assert!(warm.x > warm.y && warm.y > warm.z);
+
+        // D65 is close to a 6500K black body, so it should come out nearly white.
+        let white = blackbody_color(6500.0);
+        assert!((white.x - white.z).abs() < 0.15);
+        assert_eq!(blackbody_color(0.0), Color::zero());
+    }
+
+    #[test]
+    fn test_wavelength_weights_average_to_white() {
+        let n = 4000;
+        let mut sum = Color::zero();
+        for i in 0..n {
+            let (_, weight) = sample_visible_wavelength((i as f64 + 0.5) / n as f64);
+            sum += weight;
+        }
+        let mean = sum / n as f64;
+        assert!((mean - Color::ones()).length() < 1e-3);
+
+        let (lambda, weight) = sample_visible_wavelength(0.2);
+        assert_eq!(lambda, LAMBDA_MIN + 0.2 * (LAMBDA_MAX - LAMBDA_MIN));
+        assert!(weight.z > weight.x);
+    }
+}
@@ -5,9 +5,9 @@ use rand::Rng;
 
 use crate::Interval;
 
-use super::vec3::Vec3;
+use super::vec3::{Color, Vec3};
 
-pub fn write_color(out: &mut impl Write, color: Vec3) -> Result<()> {
+pub fn write_color(out: &mut impl Write, color: Color) -> Result<()> {
     // Translate the [0,1] component values to the byte range [0,255].
     let r = linear_to_gamma(color.x);
     let g = linear_to_gamma(color.y);
@@ -67,6 +67,33 @@ pub fn random_on_hemisphere(normal: Vec3) -> Vec3 {
     }
 }
 
+/// Cosine-distributed direction on the hemisphere around +z.
+pub fn random_cosine_direction() -> Vec3 {
+    let r1 = random_f64();
+    let r2 = random_f64();
+
+    let phi = 2.0 * std::f64::consts::PI * r1;
+    let x = phi.cos() * r2.sqrt();
+    let y = phi.sin() * r2.sqrt();
+    let z = (1.0 - r2).sqrt();
+
+    Vec3::new(x, y, z)
+}
+
+/// Uniformly distributed direction within the cone of directions, around +z, that
+/// covers a sphere of `radius` at squared distance `distance_squared`.
+pub fn random_to_sphere(radius: f64, distance_squared: f64) -> Vec3 {
+    let r1 = random_f64();
+    let r2 = random_f64();
+    let z = 1.0 + r2 * ((1.0 - radius * radius / distance_squared).max(0.0).sqrt() - 1.0);
+
+    let phi = 2.0 * std::f64::consts::PI * r1;
+    let x = phi.cos() * (1.0 - z * z).max(0.0).sqrt();
+    let y = phi.sin() * (1.0 - z * z).max(0.0).sqrt();
+
+    Vec3::new(x, y, z)
+}
+
 pub fn random_vector() -> Vec3 {
     Vec3 {
         x: random_f64_range(0.0, 1.0),
@@ -91,6 +118,32 @@ pub fn linear_to_gamma(linear_component: f64) -> f64 {
     }
 }
 
+/// Relative luminance of a linear sRGB color.
+pub fn luminance(color: Color) -> f64 {
+    0.2126 * color.x + 0.7152 * color.y + 0.0722 * color.z
+}
+
+/// Converts CIE XYZ to linear sRGB (D65 white point).
+pub fn xyz_to_linear_srgb(xyz: Vec3) -> Color {
+    Vec3::new(
+        3.2406 * xyz.x - 1.5372 * xyz.y - 0.4986 * xyz.z,
+        -0.9689 * xyz.x + 1.8758 * xyz.y + 0.0415 * xyz.z,
+        0.0557 * xyz.x - 0.2040 * xyz.y + 1.0570 * xyz.z,
+    )
+}
+
+/// Veach's power heuristic (beta = 2): the multiple importance sampling weight of a
+/// sample drawn with density `pdf` when another strategy could have produced it with
+/// density `other_pdf`.
+pub fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let f = pdf * pdf;
+    let g = other_pdf * other_pdf;
+    if f + g == 0.0 {
+        return 0.0;
+    }
+    f / (f + g)
+}
+
 pub fn reflect(v: Vec3, n: Vec3) -> Vec3 {
     v - 2.0 * v.dot(n) * n
 }
@@ -101,3 +154,190 @@ pub fn refract(uv: Vec3, n: Vec3, etai_over_etat: f64) -> Vec3 {
     let r_out_parallel = -(1.0 - r_out_prep.length_squard()).abs().sqrt() * n;
     r_out_prep + r_out_parallel
 }
+
+fn is_zero(x: f64) -> bool {
+    x.abs() < 1e-9
+}
+
+/// Real roots of `c[0] + c[1] x + c[2] x^2`.
+pub fn solve_quadratic(c: [f64; 3]) -> Vec<f64> {
+    // Normal form: x^2 + 2px + q = 0
+    let p = c[1] / (2.0 * c[2]);
+    let q = c[0] / c[2];
+    let d = p * p - q;
+
+    if is_zero(d) {
+        vec![-p]
+    } else if d < 0.0 {
+        vec![]
+    } else {
+        let sqrt_d = d.sqrt();
+        vec![sqrt_d - p, -sqrt_d - p]
+    }
+}
+
+/// Real roots of `c[0] + c[1] x + c[2] x^2 + c[3] x^3` (Cardano).
+pub fn solve_cubic(c: [f64; 4]) -> Vec<f64> {
+    // Normal form: x^3 + Ax^2 + Bx + C = 0
+    let a = c[2] / c[3];
+    let b = c[1] / c[3];
+    let cc = c[0] / c[3];
+
+    // Substitute x = y - A/3 to eliminate the quadric term: y^3 + 3py + 2q = 0
+    let sq_a = a * a;
+    let p = 1.0 / 3.0 * (-1.0 / 3.0 * sq_a + b);
+    let q = 1.0 / 2.0 * (2.0 / 27.0 * a * sq_a - 1.0 / 3.0 * a * b + cc);
+
+    let cb_p = p * p * p;
+    let d = q * q + cb_p;
+
+    let mut roots = if is_zero(d) {
+        if is_zero(q) {
+            vec![0.0]
+        } else {
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if d < 0.0 {
+        // Casus irreducibilis: three real solutions.
+        let phi = 1.0 / 3.0 * (-q / (-cb_p).sqrt()).acos();
+        let t = 2.0 * (-p).sqrt();
+        vec![
+            t * phi.cos(),
+            -t * (phi + std::f64::consts::PI / 3.0).cos(),
+            -t * (phi - std::f64::consts::PI / 3.0).cos(),
+        ]
+    } else {
+        let sqrt_d = d.sqrt();
+        let u = (sqrt_d - q).cbrt();
+        let v = -(sqrt_d + q).cbrt();
+        vec![u + v]
+    };
+
+    let sub = 1.0 / 3.0 * a;
+    for root in roots.iter_mut() {
+        *root -= sub;
+    }
+    roots
+}
+
+/// Real roots of `c[0] + c[1] x + c[2] x^2 + c[3] x^3 + c[4] x^4` (Ferrari), polished
+/// with a couple of Newton steps since the closed form loses precision.
+pub fn solve_quartic(c: [f64; 5]) -> Vec<f64> {
+    // Normal form: x^4 + Ax^3 + Bx^2 + Cx + D = 0
+    let a = c[3] / c[4];
+    let b = c[2] / c[4];
+    let cc = c[1] / c[4];
+    let d = c[0] / c[4];
+
+    // Substitute x = y - A/4 to eliminate the cubic term: y^4 + py^2 + qy + r = 0
+    let sq_a = a * a;
+    let p = -3.0 / 8.0 * sq_a + b;
+    let q = 1.0 / 8.0 * sq_a * a - 1.0 / 2.0 * a * b + cc;
+    let r = -3.0 / 256.0 * sq_a * sq_a + 1.0 / 16.0 * sq_a * b - 1.0 / 4.0 * a * cc + d;
+
+    let mut roots = if is_zero(r) {
+        // No absolute term: y(y^3 + py + q) = 0
+        let mut roots = solve_cubic([q, p, 0.0, 1.0]);
+        roots.push(0.0);
+        roots
+    } else {
+        // Solve the resolvent cubic and take one real solution to build two quadratics.
+        let z = solve_cubic([
+            1.0 / 2.0 * r * p - 1.0 / 8.0 * q * q,
+            -r,
+            -1.0 / 2.0 * p,
+            1.0,
+        ])[0];
+
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = if is_zero(u) {
+            0.0
+        } else if u > 0.0 {
+            u.sqrt()
+        } else {
+            return vec![];
+        };
+        let v = if is_zero(v) {
+            0.0
+        } else if v > 0.0 {
+            v.sqrt()
+        } else {
+            return vec![];
+        };
+
+        let (v1, v2) = if q < 0.0 { (-v, v) } else { (v, -v) };
+        let mut roots = solve_quadratic([z - u, v1, 1.0]);
+        roots.extend(solve_quadratic([z + u, v2, 1.0]));
+        roots
+    };
+
+    let sub = 1.0 / 4.0 * a;
+    for root in roots.iter_mut() {
+        *root -= sub;
+        for _ in 0..2 {
+            let x = *root;
+            let f = (((c[4] * x + c[3]) * x + c[2]) * x + c[1]) * x + c[0];
+            let df = ((4.0 * c[4] * x + 3.0 * c[3]) * x + 2.0 * c[2]) * x + c[1];
+            if df.abs() > 1e-12 {
+                *root = x - f / df;
+            }
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut roots: Vec<f64>) -> Vec<f64> {
+        roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        roots
+    }
+
+    fn assert_roots(actual: Vec<f64>, expected: &[f64]) {
+        let actual = sorted(actual);
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_solve_quadratic() {
+        // (x - 1)(x - 3)
+        assert_roots(solve_quadratic([3.0, -4.0, 1.0]), &[1.0, 3.0]);
+        assert_roots(solve_quadratic([1.0, 0.0, 1.0]), &[]);
+    }
+
+    #[test]
+    fn test_solve_cubic() {
+        // (x - 1)(x - 2)(x - 3)
+        assert_roots(solve_cubic([-6.0, 11.0, -6.0, 1.0]), &[1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_solve_quartic() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        assert_roots(
+            solve_quartic([24.0, -50.0, 35.0, -10.0, 1.0]),
+            &[1.0, 2.0, 3.0, 4.0],
+        );
+        // (x^2 + 1)(x - 0.5)(x + 2)
+        assert_roots(solve_quartic([-1.0, 1.5, 0.0, 1.5, 1.0]), &[-2.0, 0.5]);
+    }
+
+    #[test]
+    fn test_power_heuristic() {
+        assert_eq!(power_heuristic(1.0, 1.0), 0.5);
+        assert_eq!(power_heuristic(3.0, 1.0), 0.9);
+        assert_eq!(power_heuristic(0.0, 2.0), 0.0);
+        assert_eq!(power_heuristic(0.0, 0.0), 0.0);
+        // The weights of two strategies for the same sample always sum to one.
+        for &(a, b) in &[(0.2, 5.0), (1e-3, 1e3), (7.0, 7.5)] {
+            assert!((power_heuristic(a, b) + power_heuristic(b, a) - 1.0).abs() < 1e-12);
+        }
+    }
+}
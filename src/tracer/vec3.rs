@@ -1,5 +1,14 @@
 use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub};
 
+use crate::degrees_to_radians;
+
+/// Positions in space.
+pub type Point3 = Vec3;
+/// Surface normals; transform with the inverse transpose, not like directions.
+pub type Normal3 = Vec3;
+/// Linear RGB colors and radiance.
+pub type Color = Vec3;
+
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
 pub struct Vec3 {
     pub x: f64,
@@ -187,6 +196,439 @@ impl Div<f64> for Vec3 {
     }
 }
 
+/// A 4x4 row-major matrix for affine transforms of points, vectors and normals.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat4 {
+    pub m: [[f64; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn new(m: [[f64; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn identity() -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        Self { m }
+    }
+
+    pub fn translation(offset: Vec3) -> Self {
+        let mut t = Self::identity();
+        t.m[0][3] = offset.x;
+        t.m[1][3] = offset.y;
+        t.m[2][3] = offset.z;
+        t
+    }
+
+    pub fn scaling(scale: Vec3) -> Self {
+        let mut s = Self::identity();
+        s.m[0][0] = scale.x;
+        s.m[1][1] = scale.y;
+        s.m[2][2] = scale.z;
+        s
+    }
+
+    /// Counter-clockwise rotation by `degrees` around `axis`.
+    pub fn rotation(axis: Vec3, degrees: f64) -> Self {
+        Quaternion::from_axis_angle(axis, degrees).to_mat4()
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Self { m }
+    }
+
+    /// General inverse by Gauss-Jordan elimination with partial pivoting.
+    /// Returns `None` for singular matrices.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inv = Self::identity().m;
+
+        for col in 0..4 {
+            let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for j in 0..4 {
+                a[col][j] *= scale;
+                inv[col][j] *= scale;
+            }
+
+            for row in 0..4 {
+                if row != col {
+                    let factor = a[row][col];
+                    for j in 0..4 {
+                        a[row][j] -= factor * a[col][j];
+                        inv[row][j] -= factor * inv[col][j];
+                    }
+                }
+            }
+        }
+
+        Some(Self { m: inv })
+    }
+
+    pub fn transform_point(&self, p: Vec3) -> Vec3 {
+        let m = &self.m;
+        let x = m[0][0] * p.x + m[0][1] * p.y + m[0][2] * p.z + m[0][3];
+        let y = m[1][0] * p.x + m[1][1] * p.y + m[1][2] * p.z + m[1][3];
+        let z = m[2][0] * p.x + m[2][1] * p.y + m[2][2] * p.z + m[2][3];
+        let w = m[3][0] * p.x + m[3][1] * p.y + m[3][2] * p.z + m[3][3];
+        if w == 1.0 {
+            Vec3::new(x, y, z)
+        } else {
+            Vec3::new(x, y, z) / w
+        }
+    }
+
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+
+    /// Transforms a normal given the matrix is the *inverse* of the point transform,
+    /// i.e. applies the inverse transpose without computing it.
+    pub fn transform_normal_by_inverse(&self, n: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * n.x + m[1][0] * n.y + m[2][0] * n.z,
+            m[0][1] * n.x + m[1][1] * n.y + m[2][1] * n.z,
+            m[0][2] * n.x + m[1][2] * n.y + m[2][2] * n.z,
+        )
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, rhs: Mat4) -> Self::Output {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Mat4 { m }
+    }
+}
+
+/// A 3x3 row-major matrix for linear transforms.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat3 {
+    pub m: [[f64; 3]; 3],
+}
+
+impl Mat3 {
+    pub fn new(m: [[f64; 3]; 3]) -> Self {
+        Self { m }
+    }
+
+    pub fn identity() -> Self {
+        Self::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Builds the matrix whose columns are `a`, `b` and `c`.
+    pub fn from_columns(a: Vec3, b: Vec3, c: Vec3) -> Self {
+        Self::new([[a.x, b.x, c.x], [a.y, b.y, c.y], [a.z, b.z, c.z]])
+    }
+
+    pub fn scaling(scale: Vec3) -> Self {
+        Self::new([
+            [scale.x, 0.0, 0.0],
+            [0.0, scale.y, 0.0],
+            [0.0, 0.0, scale.z],
+        ])
+    }
+
+    /// Counter-clockwise rotation by `degrees` around `axis`.
+    pub fn rotation(axis: Vec3, degrees: f64) -> Self {
+        Quaternion::from_axis_angle(axis, degrees).to_mat3()
+    }
+
+    pub fn transpose(&self) -> Self {
+        let m = &self.m;
+        Self::new([
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]],
+        ])
+    }
+
+    pub fn determinant(&self) -> f64 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// Inverse via the adjugate. Returns `None` for singular matrices.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let m = &self.m;
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+        };
+        let adjugate = Self::new([
+            [
+                cofactor(1, 2, 1, 2),
+                -cofactor(0, 2, 1, 2),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                -cofactor(1, 2, 0, 2),
+                cofactor(0, 2, 0, 2),
+                -cofactor(0, 1, 0, 2),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                -cofactor(0, 2, 0, 1),
+                cofactor(0, 1, 0, 1),
+            ],
+        ]);
+        Some(adjugate * (1.0 / det))
+    }
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, rhs: Mat3) -> Self::Output {
+        let mut m = [[0.0; 3]; 3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Mat3 { m }
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(self, v: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+impl Mul<f64> for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        let mut m = self.m;
+        for value in m.iter_mut().flatten() {
+            *value *= rhs;
+        }
+        Mat3 { m }
+    }
+}
+
+impl From<Mat3> for Mat4 {
+    fn from(value: Mat3) -> Self {
+        let mut m = Mat4::identity();
+        for i in 0..3 {
+            m.m[i][..3].copy_from_slice(&value.m[i]);
+        }
+        m
+    }
+}
+
+/// A rotation quaternion `w + xi + yj + zk`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Quaternion {
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+        Self { w, x, y, z }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    /// Counter-clockwise rotation by `degrees` around `axis`.
+    pub fn from_axis_angle(axis: Vec3, degrees: f64) -> Self {
+        let a = axis.unit();
+        let (sin, cos) = (degrees_to_radians(degrees) / 2.0).sin_cos();
+        Self::new(cos, a.x * sin, a.y * sin, a.z * sin)
+    }
+
+    pub fn dot(&self, other: Quaternion) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn length(&self) -> f64 {
+        self.dot(*self).sqrt()
+    }
+
+    pub fn unit(&self) -> Quaternion {
+        let length = self.length();
+        Self::new(
+            self.w / length,
+            self.x / length,
+            self.y / length,
+            self.z / length,
+        )
+    }
+
+    pub fn conjugate(&self) -> Quaternion {
+        Self::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn rotate(&self, v: Vec3) -> Vec3 {
+        self.to_mat3() * v
+    }
+
+    /// Spherical linear interpolation along the shorter arc from `a` (t = 0) to `b` (t = 1).
+    pub fn slerp(a: Quaternion, b: Quaternion, t: f64) -> Quaternion {
+        let mut cos_theta = a.dot(b);
+        let mut b = b;
+        if cos_theta < 0.0 {
+            b = Self::new(-b.w, -b.x, -b.y, -b.z);
+            cos_theta = -cos_theta;
+        }
+
+        // Fall back to a normalized lerp when the rotations are nearly identical.
+        let (wa, wb) = if cos_theta > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1.0 - t) * theta).sin() / sin_theta,
+                (t * theta).sin() / sin_theta,
+            )
+        };
+
+        Self::new(
+            wa * a.w + wb * b.w,
+            wa * a.x + wb * b.x,
+            wa * a.y + wb * b.y,
+            wa * a.z + wb * b.z,
+        )
+        .unit()
+    }
+
+    pub fn to_mat3(&self) -> Mat3 {
+        let Quaternion { w, x, y, z } = self.unit();
+        Mat3::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ])
+    }
+
+    pub fn to_mat4(&self) -> Mat4 {
+        self.to_mat3().into()
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Quaternion;
+
+    /// Hamilton product: rotating by `self * rhs` applies `rhs` first.
+    fn mul(self, rhs: Quaternion) -> Self::Output {
+        Quaternion::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+
+/// An orthonormal basis with `w` along a chosen direction, used to move vectors between
+/// a local frame (where `w` is +z) and world space.
+#[derive(Clone, Copy, Debug)]
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    pub fn new(n: Vec3) -> Self {
+        let w = n.unit();
+        let a = if w.x.abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let u = w.cross(a).unit();
+        let v = w.cross(u);
+        Self { u, v, w }
+    }
+
+    /// Transform from basis coordinates to world space.
+    pub fn transform(&self, v: Vec3) -> Vec3 {
+        v.x * self.u + v.y * self.v + v.z * self.w
+    }
+
+    /// Transform from world space to basis coordinates.
+    pub fn to_local(&self, v: Vec3) -> Vec3 {
+        Vec3::new(v.dot(self.u), v.dot(self.v), v.dot(self.w))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -278,4 +720,77 @@ mod tests {
             Vec3::new(1.0 / 3.0, 2.0 / 3.0, -2.0 / 3.0)
         );
     }
+
+    fn assert_vec_eq(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_mat4_rotation() {
+        let r = Mat4::rotation(Vec3::new(0.0, 1.0, 0.0), 90.0);
+        assert_vec_eq(
+            r.transform_vector(Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::new(0.0, 0.0, -1.0),
+        );
+    }
+
+    #[test]
+    fn test_mat4_inverse() {
+        let m = Mat4::translation(Vec3::new(1.0, 2.0, 3.0))
+            * Mat4::rotation(Vec3::new(1.0, 1.0, 0.0), 30.0)
+            * Mat4::scaling(Vec3::new(2.0, 0.5, 3.0));
+        let inv = m.inverse().unwrap();
+        let p = Vec3::new(0.3, -1.2, 4.0);
+        assert_vec_eq(inv.transform_point(m.transform_point(p)), p);
+        assert!(Mat4::scaling(Vec3::new(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+
+    #[test]
+    fn test_mat4_normal() {
+        // A normal must stay perpendicular to the surface under non-uniform scaling.
+        let m = Mat4::scaling(Vec3::new(4.0, 1.0, 1.0));
+        let inv = m.inverse().unwrap();
+        let tangent = m.transform_vector(Vec3::new(1.0, -1.0, 0.0));
+        let normal = inv.transform_normal_by_inverse(Vec3::new(1.0, 1.0, 0.0));
+        assert!(tangent.dot(normal).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_mat3_inverse() {
+        let m = Mat3::rotation(Vec3::new(0.2, 1.0, 0.5), 40.0)
+            * Mat3::scaling(Vec3::new(2.0, 3.0, 0.5));
+        let p = Vec3::new(1.0, -2.0, 0.5);
+        assert_vec_eq(m.inverse().unwrap() * (m * p), p);
+        assert_eq!(Mat3::identity().determinant(), 1.0);
+    }
+
+    #[test]
+    fn test_quaternion_matches_mat4_rotation() {
+        let axis = Vec3::new(1.0, 2.0, -1.0);
+        let q = Quaternion::from_axis_angle(axis, 75.0);
+        let v = Vec3::new(0.3, -0.4, 2.0);
+        assert_vec_eq(q.rotate(v), Mat4::rotation(axis, 75.0).transform_vector(v));
+    }
+
+    #[test]
+    fn test_quaternion_slerp() {
+        let y = Vec3::new(0.0, 1.0, 0.0);
+        let a = Quaternion::identity();
+        let b = Quaternion::from_axis_angle(y, 90.0);
+        let half = Quaternion::slerp(a, b, 0.5);
+        let expected = Quaternion::from_axis_angle(y, 45.0);
+        assert!((half.dot(expected).abs() - 1.0).abs() < 1e-12);
+        assert_vec_eq(
+            Quaternion::slerp(a, b, 1.0).rotate(Vec3::new(1.0, 0.0, 0.0)),
+            Vec3::new(0.0, 0.0, -1.0),
+        );
+    }
+
+    #[test]
+    fn test_onb_round_trip() {
+        let onb = Onb::new(Vec3::new(1.0, 1.0, 0.0));
+        let v = Vec3::new(0.5, -2.0, 1.0);
+        assert_vec_eq(onb.transform(onb.to_local(v)), v);
+        assert!((onb.u.cross(onb.v) - onb.w).length() < 1e-12);
+    }
 }